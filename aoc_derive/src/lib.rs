@@ -0,0 +1,121 @@
+//! Derive macros for `aoc_utils`.
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{Data, DeriveInput, Error, Fields, Ident, LitStr, parse_macro_input};
+
+/// Derive `FromStr` from a format string, such as
+/// `#[aoc(format = "p={pos} v={vel}")]`.  Each `{field}` is parsed with that
+/// field's own `FromStr`, and every other character must match exactly.
+/// Every field of the struct must appear once in the format.
+///
+/// The error type is `aoc_utils::ScanError`, as for the `scan!` macro.
+#[proc_macro_derive(FromLine, attributes(aoc))]
+pub fn derive_from_line(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new_spanned(&input, "FromLine needs named fields")),
+        },
+        _ => return Err(Error::new_spanned(&input, "FromLine only works on structs")),
+    };
+    let format = format_attr(&input)?;
+    let (pattern, names) = split_format(&format)?;
+
+    let mut values = Vec::new();
+    for (index, name) in names.iter().enumerate() {
+        let field = fields
+            .iter()
+            .find(|f| f.ident.as_ref().is_some_and(|i| i == name))
+            .ok_or_else(|| Error::new(format.span(), format!("no field named `{}`", name)))?;
+        let ident = &field.ident;
+        let ty = &field.ty;
+        values.push(quote! {
+            #ident: ::aoc_utils::scan::parse_field::<#ty>(#index, fields[#index])?
+        });
+    }
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named fields");
+        match names.iter().filter(|n| *n == ident).count() {
+            0 => {
+                return Err(Error::new_spanned(
+                    ident,
+                    "field is missing from the format",
+                ));
+            }
+            1 => {}
+            _ => {
+                return Err(Error::new(
+                    format.span(),
+                    format!("`{}` appears twice", ident),
+                ));
+            }
+        }
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::std::str::FromStr for #name #ty_generics #where_clause {
+            type Err = ::aoc_utils::scan::ScanError;
+
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                let fields = ::aoc_utils::scan::scan_fields(s, #pattern)?;
+                Ok(Self { #(#values),* })
+            }
+        }
+    })
+}
+
+/// The string from `#[aoc(format = "...")]`
+fn format_attr(input: &DeriveInput) -> syn::Result<LitStr> {
+    let mut format = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("aoc")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("format") {
+                format = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `format`"))
+            }
+        })?;
+    }
+    format.ok_or_else(|| Error::new(Span::call_site(), "FromLine needs #[aoc(format = \"...\")]"))
+}
+
+/// Turn "p={pos} v={vel}" into the `scan_fields` pattern "p={} v={}", and
+/// the field names in order
+fn split_format(format: &LitStr) -> syn::Result<(String, Vec<Ident>)> {
+    let text = format.value();
+    let mut pattern = String::new();
+    let mut names = Vec::new();
+    let mut rest = text.as_str();
+    while let Some(open) = rest.find('{') {
+        pattern.push_str(&rest[..open]);
+        let close = rest[open..]
+            .find('}')
+            .ok_or_else(|| Error::new(format.span(), "unclosed `{` in format"))?;
+        let name = &rest[open + 1..open + close];
+        let ident = syn::parse_str::<Ident>(name).map_err(|_| {
+            Error::new(format.span(), format!("`{{{}}}` is not a field name", name))
+        })?;
+        if pattern.ends_with("{}") {
+            return Err(Error::new(
+                format.span(),
+                "fields must be separated by text",
+            ));
+        }
+        pattern.push_str("{}");
+        names.push(ident);
+        rest = &rest[open + close + 1..];
+    }
+    pattern.push_str(rest);
+    Ok((pattern, names))
+}
@@ -1,64 +1,214 @@
 use anyhow::Result;
+use std::any::type_name;
+#[cfg(feature = "nightly")]
 use std::str::pattern::Pattern;
 use thiserror::Error;
 
+use crate::Location;
+use crate::{Separator, split_on};
+
 #[derive(Error, Debug)]
 pub enum ConvertError {
     #[error("Failed to convert {0}")]
     Failed(char),
-    #[error("Failed to convert from string")]
-    NumberFromStr,
-    #[error("Failed to parse data to type T")]
-    TryFromFailed,
+    #[error("Failed to convert digit at {0}")]
+    NotADigit(Location),
+    #[error("Failed to convert from string at {0}")]
+    NumberFromStr(Location),
+    #[error("Failed to parse data to type T at {0}")]
+    TryFromFailed(Location),
 }
 
 /// Convert from char to numeric type T (such as u8, u32, etc).
 /// Returns an error if the char cannot be converted to a number
-pub fn c_to_num<T: std::convert::From<u8>>(c: char) -> Result<T> {
-    let result = c.to_digit(10).ok_or(ConvertError::Failed(c))? as u8;
-    Ok(result.into())
+pub fn c_to_num<T: TryFrom<u8>>(c: char) -> Result<T> {
+    c_to_num_radix(c, 10)
+}
+
+/// Convert from a char in base `radix` to numeric type T, so 'f' is 15 in
+/// base 16.  Returns an error if the char is not a digit in that base.
+/// Panics if `radix` is more than 36.
+pub fn c_to_num_radix<T: TryFrom<u8>>(c: char, radix: u32) -> Result<T> {
+    Ok(digit(c, radix).ok_or(ConvertError::Failed(c))?)
+}
+
+/// The value of `c` in base `radix`, if it is a digit that fits T
+fn digit<T: TryFrom<u8>>(c: char, radix: u32) -> Option<T> {
+    c.to_digit(radix).and_then(|d| T::try_from(d as u8).ok())
 }
 
 /// Convert a single string to digits
 /// "123" becomes [1,2,3]
-/// This method is unsafe, and will panic if character is not a digit.
-pub fn line_to_digits<T: std::convert::From<u8>>(line: &str) -> Result<Vec<T>> {
-    line.chars().map(c_to_num).collect()
+/// Returns an error, with its location, if a character is not a digit.
+pub fn line_to_digits<T: TryFrom<u8>>(line: &str) -> Result<Vec<T>> {
+    line_to_digits_radix(line, 10)
+}
+
+/// Convert a single string to digits in base `radix`
+/// "1f" becomes [1, 15] in base 16, and "101" becomes [1, 0, 1] in base 2.
+/// Returns an error, with its location, if a character is not a digit.
+pub fn line_to_digits_radix<T: TryFrom<u8>>(line: &str, radix: u32) -> Result<Vec<T>> {
+    Ok(digits_in_line(line, 1, radix)?)
+}
+
+/// Convert a single string to digits, skipping anything that is not a digit
+/// "1-2 3" becomes [1, 2, 3]
+pub fn line_to_valid_digits<T: TryFrom<u8>>(line: &str) -> Vec<T> {
+    line_to_valid_digits_radix(line, 10)
+}
+
+/// Convert a single string to digits, skipping anything that is not a
+/// digit in base `radix`
+/// "f-z1" becomes [15, 1] in base 16.
+pub fn line_to_valid_digits_radix<T: TryFrom<u8>>(line: &str, radix: u32) -> Vec<T> {
+    line.chars().filter_map(|c| digit(c, radix)).collect()
 }
 
-/// Convert a single string to numbers
+/// Convert a single string to digits
 /// "123" becomes [1,2,3]
-/// This method is unsafe, and will panic if character is not a digit.
-pub fn unsafe_line_to_digits<T: std::convert::From<u8>>(line: &str) -> Vec<T> {
+/// This method will panic, with the location, if a character is not a digit.
+pub fn unsafe_line_to_digits<T: TryFrom<u8>>(line: &str) -> Vec<T> {
+    unsafe_line_to_digits_radix(line, 10)
+}
+
+/// Convert a single string to digits in base `radix`
+/// This method will panic, with the location, if a character is not a digit.
+pub fn unsafe_line_to_digits_radix<T: TryFrom<u8>>(line: &str, radix: u32) -> Vec<T> {
+    digits_in_line(line, 1, radix).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Digits of line number `line_no`, for error locations
+fn digits_in_line<T: TryFrom<u8>>(
+    line: &str,
+    line_no: usize,
+    radix: u32,
+) -> Result<Vec<T>, ConvertError> {
     line.chars()
-        .map(|c| (c.to_digit(10).unwrap() as u8).into())
+        .enumerate()
+        .map(|(i, c)| {
+            digit(c, radix).ok_or_else(|| {
+                let expected = match radix {
+                    10 => "a digit".to_string(),
+                    _ => format!("a base {} digit", radix),
+                };
+                ConvertError::NotADigit(Location::in_line(line_no, line, i + 1, c, expected))
+            })
+        })
         .collect()
 }
 
 /// Convert `\n` delimited lines to digits
 /// "123\n456" becomes [[1, 2, 3], [4, 5, 6]]
-pub fn data_to_digits<T: std::convert::From<u8>>(data: &str) -> Result<Vec<Vec<T>>> {
-    data.lines().map(line_to_digits).collect()
+/// Returns an error, with its location, if a character is not a digit.
+pub fn data_to_digits<T: TryFrom<u8>>(data: &str) -> Result<Vec<Vec<T>>> {
+    data_to_digits_radix(data, 10)
+}
+
+/// Convert `\n` delimited lines to digits in base `radix`
+/// "10\n01" becomes [[1, 0], [0, 1]] in base 2.
+/// Returns an error, with its location, if a character is not a digit.
+pub fn data_to_digits_radix<T: TryFrom<u8>>(data: &str, radix: u32) -> Result<Vec<Vec<T>>> {
+    data.lines()
+        .enumerate()
+        .map(|(i, line)| Ok(digits_in_line(line, i + 1, radix)?))
+        .collect()
+}
+
+/// Convert `\n` delimited lines to digits, skipping anything that is not
+/// a digit
+/// "1.2\n3.4" becomes [[1, 2], [3, 4]]
+pub fn data_to_valid_digits<T: TryFrom<u8>>(data: &str) -> Vec<Vec<T>> {
+    data_to_valid_digits_radix(data, 10)
+}
+
+/// Convert `\n` delimited lines to digits, skipping anything that is not
+/// a digit in base `radix`
+/// "1.0\n2.1" becomes [[1, 0], [1]] in base 2.
+pub fn data_to_valid_digits_radix<T: TryFrom<u8>>(data: &str, radix: u32) -> Vec<Vec<T>> {
+    data.lines()
+        .map(|line| line_to_valid_digits_radix(line, radix))
+        .collect()
 }
 
 /// Convert `\n` delimited lines to digits
 /// "123\n456" becomes [[1, 2, 3], [4, 5, 6]]
-pub fn unsafe_data_to_digits<T: std::convert::From<u8>>(data: &str) -> Vec<Vec<T>> {
-    data.lines().map(unsafe_line_to_digits).collect()
+/// This method will panic, with the location, if a character is not a digit.
+pub fn unsafe_data_to_digits<T: TryFrom<u8>>(data: &str) -> Vec<Vec<T>> {
+    unsafe_data_to_digits_radix(data, 10)
+}
+
+/// Convert `\n` delimited lines to digits in base `radix`
+/// This method will panic, with the location, if a character is not a digit.
+pub fn unsafe_data_to_digits_radix<T: TryFrom<u8>>(data: &str, radix: u32) -> Vec<Vec<T>> {
+    data_to_digits_radix(data, radix).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Splits a line on `sep` and parses each into T
+/// `sep` can be a char, a `&str`, a list of chars, or a closure.
+pub fn line_to_numbers<T: std::str::FromStr>(line: &str, sep: impl Separator) -> Result<Vec<T>> {
+    Ok(numbers_in_line(line, 1, split_on(line.trim(), &sep))?)
 }
 
-/// Splits a line on `pat` and parses each into T
-pub fn line_to_numbers<T: std::str::FromStr>(line: &str, pat: impl Pattern) -> Result<Vec<T>> {
-    line.trim()
-        .split(pat)
+/// Splits each `\n` delimited line on `sep` and parses each into T
+/// "1 2\n3 4" becomes [[1, 2], [3, 4]]
+pub fn data_to_numbers<T: std::str::FromStr>(
+    data: &str,
+    sep: impl Separator,
+) -> Result<Vec<Vec<T>>> {
+    data.lines()
+        .enumerate()
+        .map(|(i, line)| Ok(numbers_in_line(line, i + 1, split_on(line.trim(), &sep))?))
+        .collect()
+}
+
+/// Splits a line on the nightly `Pattern` `pat` and parses each into T
+#[cfg(feature = "nightly")]
+pub fn line_to_numbers_pattern<T: std::str::FromStr>(
+    line: &str,
+    pat: impl Pattern,
+) -> Result<Vec<T>> {
+    Ok(numbers_in_line(line, 1, line.trim().split(pat))?)
+}
+
+/// Splits each `\n` delimited line on the nightly `Pattern` `pat` and
+/// parses each into T
+#[cfg(feature = "nightly")]
+pub fn data_to_numbers_pattern<T: std::str::FromStr>(
+    data: &str,
+    pat: impl Pattern + Clone,
+) -> Result<Vec<Vec<T>>> {
+    data.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            Ok(numbers_in_line(
+                line,
+                i + 1,
+                line.trim().split(pat.clone()),
+            )?)
+        })
+        .collect()
+}
+
+/// Parse the `pieces` of line number `line_no`, for error locations
+fn numbers_in_line<'a, T: std::str::FromStr>(
+    line: &str,
+    line_no: usize,
+    pieces: impl Iterator<Item = &'a str>,
+) -> Result<Vec<T>, ConvertError> {
+    pieces
         .filter(|s| !s.is_empty())
         .map(|s| {
-            s.trim()
-                .parse::<T>()
-                .map_err(|_| ConvertError::NumberFromStr.into())
+            let s = s.trim();
+            s.parse::<T>().map_err(|_| {
+                let offset = s.as_ptr() as usize - line.as_ptr() as usize;
+                let expected = format!("a {}", type_name::<T>());
+                let location = Location::at_offset(line, offset, s.len(), expected);
+                ConvertError::NumberFromStr(Location {
+                    line: line_no,
+                    ..location
+                })
+            })
         })
-        .collect::<Vec<Result<T>>>()
-        .into_iter()
         .collect()
 }
 
@@ -74,18 +224,40 @@ pub fn data_to_ascii_bytes(data: &str) -> Vec<Vec<u8>> {
     data.lines().map(line_to_ascii_bytes).collect()
 }
 
+/// Convert `\n` delimited lines to a grid of T, one cell per char.
+/// The error says which cell could not be converted.
 pub fn data_to_grid<T: std::convert::TryFrom<char>>(data: &str) -> Result<Vec<Vec<T>>> {
+    parse_grid(data).map_err(|location| ConvertError::TryFromFailed(location).into())
+}
+
+/// `data_to_grid`, returning the location of the first bad cell
+pub(crate) fn parse_grid<T: std::convert::TryFrom<char>>(
+    data: &str,
+) -> Result<Vec<Vec<T>>, Location> {
+    let expected = format!("a {} cell", type_name::<T>());
+    parse_grid_with(data, &expected, |_, _, c| T::try_from(c).ok())
+}
+
+/// Map each char to a cell with `f(x, y, c)`, returning the location of
+/// the first char that `f` rejects
+pub(crate) fn parse_grid_with<T>(
+    data: &str,
+    expected: &str,
+    mut f: impl FnMut(usize, usize, char) -> Option<T>,
+) -> Result<Vec<Vec<T>>, Location> {
     data.lines()
-        .map(|c| {
-            c.chars()
-                .map(|c| T::try_from(c))
-                .collect::<Vec<Result<T, _>>>()
-                .into_iter()
-                .collect::<Result<Vec<T>, _>>()
+        .enumerate()
+        .map(|(y, line)| {
+            line.chars()
+                .enumerate()
+                .map(|(x, c)| {
+                    f(x, y, c).ok_or_else(|| Location::in_line(y + 1, line, x + 1, c, expected))
+                })
+                .collect()
         })
-        .collect::<Result<Vec<Vec<T>>, _>>()
-        .map_err(|_| ConvertError::TryFromFailed.into())
+        .collect()
 }
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,12 +275,81 @@ mod tests {
         println!("{:?}", data_to_digits::<u32>(input));
     }
 
+    #[test]
+    fn test_digit_family() {
+        assert_eq!(line_to_digits::<u8>("907").unwrap(), vec![9, 0, 7]);
+        assert!(line_to_digits::<u8>("9a7").is_err());
+        assert_eq!(
+            line_to_digits_radix::<u32>("1fA", 16).unwrap(),
+            vec![1, 15, 10]
+        );
+        assert_eq!(line_to_digits_radix::<u8>("101", 2).unwrap(), vec![1, 0, 1]);
+        assert!(line_to_digits_radix::<u8>("102", 2).is_err());
+        assert_eq!(line_to_valid_digits::<i8>("1-2 x3"), vec![1, 2, 3]);
+        assert_eq!(line_to_valid_digits_radix::<u8>("f-z1", 16), vec![15, 1]);
+        assert_eq!(unsafe_line_to_digits::<f64>("42"), vec![4.0, 2.0]);
+        assert_eq!(
+            data_to_valid_digits::<usize>("1.2\n3.4"),
+            vec![vec![1, 2], vec![3, 4]]
+        );
+        assert_eq!(
+            data_to_valid_digits_radix::<u8>("1.0\n2.1", 2),
+            vec![vec![1, 0], vec![1]]
+        );
+        assert_eq!(
+            unsafe_data_to_digits_radix::<i64>("10\n01", 2),
+            vec![vec![1, 0], vec![0, 1]]
+        );
+        assert_eq!(c_to_num_radix::<u8>('z', 36).unwrap(), 35);
+    }
+
+    #[test]
+    #[should_panic(expected = "line 2, column 2")]
+    fn test_unsafe_digits_panic() {
+        unsafe_data_to_digits::<u8>("12\n3x");
+    }
+
     #[test]
     fn test_line_to_numbers() {
         let line = "  1    2 3";
         assert_eq!(vec![1, 2, 3], line_to_numbers(line, ' ').expect("oops"));
     }
 
+    #[test]
+    fn test_data_to_numbers() {
+        let data = "1, 2\n3,4";
+        let numbers = data_to_numbers::<u8>(data, ',').expect("oops");
+        assert_eq!(numbers, vec![vec![1, 2], vec![3, 4]]);
+        let err = data_to_numbers::<u8>("1\n2 x", ' ').unwrap_err();
+        assert!(err.to_string().contains("line 2, column 3"));
+    }
+
+    #[cfg(feature = "nightly")]
+    #[test]
+    fn test_numbers_pattern() {
+        assert_eq!(
+            vec![1, 2, 3],
+            line_to_numbers_pattern(" 1 2 3", ' ').expect("oops")
+        );
+        let numbers = data_to_numbers_pattern::<u8>("1;2\n3;4", ";").expect("oops");
+        assert_eq!(numbers, vec![vec![1, 2], vec![3, 4]]);
+    }
+
+    #[test]
+    fn test_error_locations() {
+        let err = line_to_numbers::<u32>("12, 3x, 4", ',').unwrap_err();
+        match err.downcast_ref::<ConvertError>() {
+            Some(ConvertError::NumberFromStr(location)) => {
+                assert_eq!((location.line, location.column), (1, 5));
+                assert_eq!(location.found, "3x");
+            }
+            other => panic!("unexpected error {:?}", other),
+        }
+        let err = data_to_digits::<u8>("123\n4a6").unwrap_err();
+        assert!(err.to_string().contains("line 2, column 2"));
+        assert!(err.to_string().ends_with("2 | 4a6\n  |  ^"));
+    }
+
     #[test]
     fn test_data_to_u8_grid() {
         let input = "....\n....\n....\n....";
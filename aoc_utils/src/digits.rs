@@ -1,98 +1,186 @@
-pub trait NumDigits {
-    fn num_digits(&self) -> usize; //𝑓(𝑛,base)=logbase(𝑛)+1
-}
+//! Work with the digits of integers, in any radix.
+//!
+//! Implemented for every primitive integer type.  Negative numbers use the
+//! digits of their absolute value, so `-123` has the digits `[1, 2, 3]`.
+use num::PrimInt;
+use num::traits::{NumCast, checked_pow};
 
-impl NumDigits for usize {
-    fn num_digits(&self) -> usize {
-        (*self as f64).log10().floor() as usize + 1
-    }
+fn radix_of<T: PrimInt>(radix: u32) -> T {
+    assert!(radix >= 2, "radix must be at least 2, got {}", radix);
+    NumCast::from(radix).expect("radix does not fit the integer type")
 }
 
-impl NumDigits for isize {
-    fn num_digits(&self) -> usize {
-        (*self as f64).log10().floor() as usize + 1
+/// The absolute value of a digit.  For negative numbers, `%` gives
+/// negative remainders.
+#[inline]
+fn abs_digit<T: PrimInt>(d: T) -> T {
+    match d < T::zero() {
+        true => T::zero() - d,
+        false => d,
     }
 }
 
-impl NumDigits for u32 {
+pub trait NumDigits {
+    /// Number of base 10 digits.  Zero has 1 digit.
     fn num_digits(&self) -> usize {
-        (*self as f64).log10().floor() as usize + 1
+        self.num_digits_radix(10)
     }
-}
 
-impl NumDigits for i32 {
-    fn num_digits(&self) -> usize {
-        (*self as f64).log10().floor() as usize + 1
-    }
+    /// Number of digits in base `radix`.  Zero has 1 digit.
+    fn num_digits_radix(&self, radix: u32) -> usize;
 }
 
-impl NumDigits for u64 {
-    fn num_digits(&self) -> usize {
-        (*self as f64).log10().floor() as usize + 1
+impl<T: PrimInt> NumDigits for T {
+    fn num_digits_radix(&self, radix: u32) -> usize {
+        let radix = radix_of::<T>(radix);
+        let mut n = *self;
+        let mut count = 1;
+        while n / radix != T::zero() {
+            n = n / radix;
+            count += 1;
+        }
+        count
     }
 }
 
-impl NumDigits for i64 {
-    fn num_digits(&self) -> usize {
-        (*self as f64).log10().floor() as usize + 1
+pub trait ToDigits<T> {
+    /// The base 10 digits, most significant first.
+    /// "123" becomes [1, 2, 3]
+    fn to_digits(&self) -> Vec<T> {
+        self.to_digits_radix(10)
     }
-}
 
-pub trait ToDigits<T> {
-    fn to_digits(&self) -> Vec<T>;
+    /// The digits in base `radix`, most significant first
+    fn to_digits_radix(&self, radix: u32) -> Vec<T>;
 }
 
-impl ToDigits<usize> for usize {
-    fn to_digits(&self) -> Vec<Self> {
+impl<T: PrimInt> ToDigits<T> for T {
+    fn to_digits_radix(&self, radix: u32) -> Vec<T> {
+        let radix = radix_of::<T>(radix);
         let mut digits = Vec::new();
         let mut n = *self;
-        while n > 9 {
-            digits.push(n % 10);
-            n /= 10;
+        loop {
+            digits.push(abs_digit(n % radix));
+            n = n / radix;
+            if n == T::zero() {
+                break;
+            }
         }
-        digits.push(n);
         digits.reverse();
         digits
     }
 }
 
-impl ToDigits<isize> for isize {
-    fn to_digits(&self) -> Vec<Self> {
-        let mut digits = Vec::new();
-        let mut n = *self;
-        while n > 9 {
-            digits.push(n % 10);
-            n /= 10;
+/// Build a number from base 10 digits, most significant first.
+/// [1, 2, 3] becomes 123.  Returns None on overflow, or for an invalid digit.
+pub fn from_digits<T: PrimInt>(digits: &[T]) -> Option<T> {
+    from_digits_radix(digits, 10)
+}
+
+/// Build a number from digits in base `radix`, most significant first.
+/// Returns None on overflow, or for an invalid digit.
+pub fn from_digits_radix<T: PrimInt>(digits: &[T], radix: u32) -> Option<T> {
+    let r = radix_of::<T>(radix);
+    digits.iter().try_fold(T::zero(), |acc, &d| {
+        if d < T::zero() || d >= r {
+            return None;
         }
-        digits.push(n);
-        digits.reverse();
-        digits
+        acc.checked_mul(&r)?.checked_add(&d)
+    })
+}
+
+/// Append the digits of `b` to `a`, so `concat(12, 345)` is `12345`.
+/// A negative `a` keeps its sign, so `concat(-12, 345)` is `-12345`.
+/// Returns None if `b` is negative, or on overflow.
+pub fn concat<T: PrimInt>(a: T, b: T) -> Option<T> {
+    if b < T::zero() {
+        return None;
+    }
+    let shift = checked_pow(radix_of::<T>(10), b.num_digits())?;
+    let a = a.checked_mul(&shift)?;
+    match a < T::zero() {
+        true => a.checked_sub(&b),
+        false => a.checked_add(&b),
     }
 }
 
-impl ToDigits<u32> for u32 {
-    fn to_digits(&self) -> Vec<Self> {
-        let mut digits = Vec::new();
-        let mut n = *self;
-        while n > 9 {
-            digits.push(n % 10);
-            n /= 10;
-        }
-        digits.push(n);
-        digits.reverse();
-        digits
+/// Split a number with an even number of digits into its two halves, so
+/// `1234` becomes `(12, 34)` and `1000` becomes `(10, 0)`.
+/// Returns None if the number of digits is odd.
+pub fn split_digits<T: PrimInt>(n: T) -> Option<(T, T)> {
+    let len = n.num_digits();
+    if !len.is_multiple_of(2) {
+        return None;
     }
+    let half = radix_of::<T>(10).pow(len as u32 / 2);
+    Some((n / half, n % half))
 }
-impl ToDigits<i32> for i32 {
-    fn to_digits(&self) -> Vec<Self> {
-        let mut digits = Vec::new();
-        let mut n = *self;
-        while n > 9 {
-            digits.push(n % 10);
-            n /= 10;
-        }
-        digits.push(n);
-        digits.reverse();
-        digits
+
+/// Reverse the digits of a number, so `1230` becomes `321`.
+/// The sign is kept.  Returns None on overflow.
+pub fn reverse_digits<T: PrimInt>(n: T) -> Option<T> {
+    let ten = radix_of::<T>(10);
+    let mut n = n;
+    let mut result = T::zero();
+    while n != T::zero() {
+        result = result.checked_mul(&ten)?.checked_add(&(n % ten))?;
+        n = n / ten;
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_num_digits() {
+        assert_eq!(0u8.num_digits(), 1);
+        assert_eq!(9usize.num_digits(), 1);
+        assert_eq!(10i32.num_digits(), 2);
+        assert_eq!((-123i64).num_digits(), 3);
+        assert_eq!(u64::MAX.num_digits(), 20);
+        assert_eq!(i128::MIN.num_digits(), 39);
+        assert_eq!(999_999_999_999_999_999u64.num_digits(), 18);
+        assert_eq!(255u8.num_digits_radix(2), 8);
+        assert_eq!(255u32.num_digits_radix(16), 2);
+    }
+
+    #[test]
+    fn test_to_digits() {
+        assert_eq!(0usize.to_digits(), vec![0]);
+        assert_eq!(1203u32.to_digits(), vec![1, 2, 0, 3]);
+        assert_eq!((-123isize).to_digits(), vec![1, 2, 3]);
+        assert_eq!(i8::MIN.to_digits(), vec![1, 2, 8]);
+        assert_eq!(10u8.to_digits_radix(2), vec![1, 0, 1, 0]);
+        assert_eq!(0xbeefu16.to_digits_radix(16), vec![11, 14, 14, 15]);
+    }
+
+    #[test]
+    fn test_from_digits() {
+        assert_eq!(from_digits(&[1u32, 2, 3]), Some(123));
+        assert_eq!(from_digits_radix(&[1u8, 0, 1, 0], 2), Some(10));
+        assert_eq!(from_digits(&[2u8, 5, 6]), None);
+        assert_eq!(from_digits(&[1u8, 10]), None);
+    }
+
+    #[test]
+    fn test_concat() {
+        assert_eq!(concat(12u64, 345), Some(12345));
+        assert_eq!(concat(15u64, 0), Some(150));
+        assert_eq!(concat(u64::MAX, 1), None);
+        assert_eq!(concat(-12i64, 345), Some(-12345));
+        assert_eq!(concat(-1i64, 0), Some(-10));
+        assert_eq!(concat(12i64, -345), None);
+    }
+
+    #[test]
+    fn test_split_and_reverse() {
+        assert_eq!(split_digits(1234u64), Some((12, 34)));
+        assert_eq!(split_digits(1000u64), Some((10, 0)));
+        assert_eq!(split_digits(123u64), None);
+        assert_eq!(reverse_digits(1230u32), Some(321));
+        assert_eq!(reverse_digits(-12i32), Some(-21));
+        assert_eq!(reverse_digits(4_000_000_009u32), None);
     }
 }
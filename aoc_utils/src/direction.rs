@@ -1,5 +1,9 @@
+use num::Signed;
+use std::ops::{Add, AddAssign};
 use thiserror::Error;
 
+use crate::{Coord, Point};
+
 #[derive(Debug, Error, PartialEq)]
 pub enum DirectionError {
     #[error("Error parsing Direction")]
@@ -44,7 +48,7 @@ impl From<u8> for Direction {
 impl Direction {
     /// Useful if you want to limit directions to the 4 major points: North, South, East, and West
     pub fn is_major(&self) -> bool {
-        *self as u8 % 2 == 0
+        (*self as u8).is_multiple_of(2)
     }
 
     /// Turn right to the next major direction (North, South, East, West)
@@ -59,24 +63,55 @@ impl Direction {
 
     /// Turn left to the next major direction (North, West, South, East)
     pub fn left(&self) -> Self {
-        match *self as u8 {
-            0 => Self::West,
-            n => (n - 2).into(),
-        }
+        ((*self as u8 + 6) % 8).into()
     }
 
     /// Turn left to the next direction (North, NorthWest, West, SouthWest, South, etc)
     pub fn minor_left(&self) -> Self {
-        match *self as u8 {
-            0 => Self::West,
-            n => (n - 1).into(),
-        }
+        ((*self as u8 + 7) % 8).into()
     }
 
     /// Turn 180 degrees to the opposit direction
     pub fn turn_around(&self) -> Self {
         ((*self as u8 + 4) % 8).into()
     }
+
+    /// The change in position from taking one step in this direction.
+    /// North is up, so it decreases `y`.
+    pub fn offset(&self) -> Point {
+        let (x, y) = match self {
+            Self::North => (0, -1),
+            Self::NorthEast => (1, -1),
+            Self::East => (1, 0),
+            Self::SouthEast => (1, 1),
+            Self::South => (0, 1),
+            Self::SouthWest => (-1, 1),
+            Self::West => (-1, 0),
+            Self::NorthWest => (-1, -1),
+        };
+        Point { x, y }
+    }
+}
+
+impl<T: Coord + Signed> Point<T> {
+    /// Move `n` steps in direction `dir`
+    pub fn step(&self, dir: Direction, n: usize) -> Self {
+        let offset = dir.offset();
+        *self + Self::offset(offset.x, offset.y).mul(n)
+    }
+}
+
+impl<T: Coord + Signed> Add<Direction> for Point<T> {
+    type Output = Self;
+    fn add(self, rhs: Direction) -> Self::Output {
+        self.step(rhs, 1)
+    }
+}
+
+impl<T: Coord + Signed> AddAssign<Direction> for Point<T> {
+    fn add_assign(&mut self, rhs: Direction) {
+        *self = self.step(rhs, 1);
+    }
 }
 
 impl std::str::FromStr for Direction {
@@ -132,6 +167,9 @@ mod tests {
         assert_eq!(Direction::East.left(), Direction::North);
         assert_eq!(Direction::South.left(), Direction::East);
         assert_eq!(Direction::West.left(), Direction::South);
+        assert_eq!(Direction::NorthEast.left(), Direction::NorthWest);
+        assert_eq!(Direction::North.minor_left(), Direction::NorthWest);
+        assert_eq!(Direction::SouthWest.minor_left(), Direction::South);
     }
     #[test]
     fn test_turn_around() {
@@ -145,6 +183,15 @@ mod tests {
         assert_eq!(Direction::NorthWest.turn_around(), Direction::SouthEast);
     }
 
+    #[test]
+    fn test_offset() {
+        let p = Point { x: 3, y: 3 };
+        assert_eq!(p + Direction::North, p.north());
+        assert_eq!(p + Direction::SouthWest, p.south_west());
+        assert_eq!(p.step(Direction::East, 3), Point { x: 6, y: 3 });
+        assert_eq!(Point::<i64>::new(0, 0) + Direction::South, Point::new(0, 1));
+    }
+
     #[test]
     fn test_from_str() {
         assert_eq!("west".parse(), Ok(Direction::West));
@@ -0,0 +1,205 @@
+//! Area and lattice point counting for polygons on a grid.
+//!
+//! Uses the shoelace formula for area, and Pick's theorem to count the
+//! points inside.  All math is done with `i128` so large dig plans don't overflow.
+use num::Integer;
+use num::traits::NumCast;
+use std::collections::HashSet;
+
+use crate::{Coord, Direction, Point};
+
+/// A closed polygon with lattice point vertices.
+/// The last vertex connects back to the first.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Polygon {
+    pub vertices: Vec<Point<i128>>,
+}
+
+impl Polygon {
+    /// Build a polygon from its corners, in order (either clockwise or counter clockwise).
+    /// Repeating the first vertex at the end is allowed.
+    pub fn from_vertices<T: Coord>(vertices: impl IntoIterator<Item = Point<T>>) -> Self {
+        let mut vertices: Vec<Point<i128>> = vertices
+            .into_iter()
+            .map(|p| Point {
+                x: NumCast::from(p.x).expect("coordinate does not fit in i128"),
+                y: NumCast::from(p.y).expect("coordinate does not fit in i128"),
+            })
+            .collect();
+        if vertices.len() > 1 && vertices.first() == vertices.last() {
+            vertices.pop();
+        }
+        Self { vertices }
+    }
+
+    /// Build a polygon by following a list of moves from `start`, such as a dig plan.
+    /// The moves should end back at `start`.
+    pub fn from_moves(start: Point, moves: impl IntoIterator<Item = (Direction, usize)>) -> Self {
+        let start = Point::<i128> {
+            x: start.x as i128,
+            y: start.y as i128,
+        };
+        let mut vertices = vec![start];
+        let mut pos = start;
+        for (dir, n) in moves {
+            pos = pos.step(dir, n);
+            vertices.push(pos);
+        }
+        Self::from_vertices(vertices)
+    }
+
+    /// Twice the enclosed area.  Always exact, since the area of a lattice
+    /// polygon is a multiple of 1/2.
+    pub fn double_area(&self) -> i128 {
+        let n = self.vertices.len();
+        (0..n)
+            .map(|i| {
+                let (a, b) = (self.vertices[i], self.vertices[(i + 1) % n]);
+                a.x * b.y - b.x * a.y
+            })
+            .sum::<i128>()
+            .abs()
+    }
+
+    /// The enclosed area, using the shoelace formula.
+    /// Rounded down if the area is not a whole number.
+    pub fn area(&self) -> i128 {
+        self.double_area() / 2
+    }
+
+    /// Length of the boundary, counted as the number of lattice points on the edges.
+    /// An edge that doubles back over another is counted again.
+    pub fn boundary(&self) -> i128 {
+        let n = self.vertices.len();
+        (0..n)
+            .map(|i| {
+                let (a, b) = (self.vertices[i], self.vertices[(i + 1) % n]);
+                (b.x - a.x).abs().gcd(&(b.y - a.y).abs())
+            })
+            .sum()
+    }
+
+    /// Number of lattice points strictly inside, using Pick's theorem.
+    /// Zero for a degenerate polygon, such as one with fewer than 3
+    /// vertices or with every vertex on one line.
+    pub fn interior(&self) -> i128 {
+        match self.double_area() {
+            0 => 0,
+            double_area => (double_area - self.boundary() + 2) / 2,
+        }
+    }
+
+    /// Number of lattice points inside or on the edges.  For a dig plan,
+    /// this is the number of cubic meters dug out.
+    /// Pick's theorem needs a simple polygon, so a degenerate polygon with
+    /// no area is counted point by point instead, with each point on an
+    /// edge that doubles back counted once.
+    pub fn enclosed(&self) -> i128 {
+        match self.double_area() {
+            0 => self.edge_points().len() as i128,
+            _ => self.interior() + self.boundary(),
+        }
+    }
+
+    /// Every distinct lattice point on the edges
+    fn edge_points(&self) -> HashSet<Point<i128>> {
+        let n = self.vertices.len();
+        let mut points = HashSet::new();
+        for i in 0..n {
+            let (a, b) = (self.vertices[i], self.vertices[(i + 1) % n]);
+            let steps = (b.x - a.x).abs().gcd(&(b.y - a.y).abs());
+            if steps == 0 {
+                points.insert(a);
+                continue;
+            }
+            let (dx, dy) = ((b.x - a.x) / steps, (b.y - a.y) / steps);
+            points.extend((0..steps).map(|k| Point {
+                x: a.x + k * dx,
+                y: a.y + k * dy,
+            }));
+        }
+        points
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_square() {
+        let square = Polygon::from_vertices([
+            Point::new(0, 0),
+            Point::new(4, 0),
+            Point::new(4, 4),
+            Point::new(0, 4),
+        ]);
+        assert_eq!(square.area(), 16);
+        assert_eq!(square.boundary(), 16);
+        assert_eq!(square.interior(), 9);
+        assert_eq!(square.enclosed(), 25);
+    }
+
+    #[test]
+    fn test_triangle() {
+        let triangle =
+            Polygon::from_vertices([Point::new(0, 0), Point::new(3, 0), Point::new(0, 3)]);
+        assert_eq!(triangle.double_area(), 9);
+        assert_eq!(triangle.boundary(), 9);
+        assert_eq!(triangle.interior(), 1);
+    }
+
+    #[test]
+    fn test_degenerate() {
+        assert_eq!(Polygon::from_vertices::<isize>([]).interior(), 0);
+        assert_eq!(Polygon::from_vertices([Point::new(3, 3)]).interior(), 0);
+        let line = Polygon::from_vertices([Point::new(0, 0), Point::new(4, 0)]);
+        assert_eq!(line.interior(), 0);
+        let flat = Polygon::from_vertices([Point::new(0, 0), Point::new(4, 0), Point::new(2, 0)]);
+        assert_eq!(flat.interior(), 0);
+
+        assert_eq!(Polygon::from_vertices::<isize>([]).enclosed(), 0);
+        assert_eq!(Polygon::from_vertices([Point::new(3, 3)]).enclosed(), 1);
+        assert_eq!(line.enclosed(), 5);
+        assert_eq!(flat.enclosed(), 5);
+        let dig = Polygon::from_moves(
+            Point::new(0, 0),
+            [(Direction::East, 4), (Direction::West, 4)],
+        );
+        assert_eq!(dig.enclosed(), 5);
+        let hook = Polygon::from_moves(
+            Point::new(0, 0),
+            [
+                (Direction::East, 4),
+                (Direction::South, 3),
+                (Direction::North, 3),
+                (Direction::West, 4),
+            ],
+        );
+        assert_eq!(hook.enclosed(), 8);
+    }
+
+    #[test]
+    fn test_dig_plan() {
+        use Direction::*;
+        // The example from 2023 day 18
+        let moves = [
+            (East, 6),
+            (South, 5),
+            (West, 2),
+            (South, 2),
+            (East, 2),
+            (South, 2),
+            (West, 5),
+            (North, 2),
+            (West, 1),
+            (North, 2),
+            (East, 2),
+            (North, 3),
+            (West, 2),
+            (North, 2),
+        ];
+        let polygon = Polygon::from_moves(Point::default(), moves);
+        assert_eq!(polygon.enclosed(), 62);
+    }
+}
@@ -0,0 +1,327 @@
+//! Linear algebra over GF(2), where adding is XOR.
+//!
+//! Light toggle puzzles are systems like this: each button flips a set of
+//! lights, and pressing a button twice does nothing.  Solve `a x = b` once,
+//! then walk the null space to find the solution with the fewest presses.
+use std::fmt::Display;
+use std::ops::BitXorAssign;
+
+/// A fixed length vector of bits
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BitVector {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitVector {
+    /// All bits cleared
+    pub fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    /// A vector with the bits at `ones` set
+    pub fn from_ones(len: usize, ones: impl IntoIterator<Item = usize>) -> Self {
+        let mut v = Self::new(len);
+        for i in ones {
+            v.set(i, true);
+        }
+        v
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    pub fn get(&self, i: usize) -> bool {
+        assert!(i < self.len, "bit {} is out of range", i);
+        self.words[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    #[inline]
+    pub fn set(&mut self, i: usize, value: bool) {
+        assert!(i < self.len, "bit {} is out of range", i);
+        match value {
+            true => self.words[i / 64] |= 1 << (i % 64),
+            false => self.words[i / 64] &= !(1 << (i % 64)),
+        }
+    }
+
+    #[inline]
+    pub fn toggle(&mut self, i: usize) {
+        assert!(i < self.len, "bit {} is out of range", i);
+        self.words[i / 64] ^= 1 << (i % 64);
+    }
+
+    /// Number of set bits
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.words.iter().all(|w| *w == 0)
+    }
+
+    /// Indexes of the set bits, in order
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len).filter(|&i| self.get(i))
+    }
+}
+
+impl BitXorAssign<&BitVector> for BitVector {
+    fn bitxor_assign(&mut self, rhs: &BitVector) {
+        assert_eq!(self.len, rhs.len, "BitVector lengths differ");
+        self.words
+            .iter_mut()
+            .zip(&rhs.words)
+            .for_each(|(a, b)| *a ^= b);
+    }
+}
+
+impl Display for BitVector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for i in 0..self.len {
+            write!(f, "{}", if self.get(i) { '1' } else { '0' })?;
+        }
+        Ok(())
+    }
+}
+
+/// A matrix over GF(2), stored as one `BitVector` per row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gf2Matrix {
+    pub rows: Vec<BitVector>,
+    pub cols: usize,
+}
+
+impl Gf2Matrix {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            rows: vec![BitVector::new(cols); rows],
+            cols,
+        }
+    }
+
+    /// Build a matrix from its columns, where each column lists the rows
+    /// that are set.  For light puzzles, each button is a column listing
+    /// the lights it toggles.
+    pub fn from_columns(rows: usize, columns: &[Vec<usize>]) -> Self {
+        let mut m = Self::new(rows, columns.len());
+        for (c, column) in columns.iter().enumerate() {
+            for &r in column {
+                m.set(r, c, true);
+            }
+        }
+        m
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        self.rows[row].get(col)
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: bool) {
+        self.rows[row].set(col, value);
+    }
+
+    /// Reduce `[self | b]` to reduced row echelon form.
+    /// Returns the reduced rows, the reduced `b`, and the pivot columns.
+    fn reduce(&self, b: &BitVector) -> (Vec<BitVector>, BitVector, Vec<usize>) {
+        let mut rows = self.rows.clone();
+        let mut rhs = b.clone();
+        let mut pivots = Vec::new();
+        let mut row = 0;
+        for col in 0..self.cols {
+            if row == rows.len() {
+                break;
+            }
+            let Some(p) = (row..rows.len()).find(|&r| rows[r].get(col)) else {
+                continue;
+            };
+            rows.swap(row, p);
+            let (a, b) = (rhs.get(row), rhs.get(p));
+            rhs.set(row, b);
+            rhs.set(p, a);
+            let pivot_row = rows[row].clone();
+            let pivot_rhs = rhs.get(row);
+            for (r, other) in rows.iter_mut().enumerate() {
+                if r != row && other.get(col) {
+                    *other ^= &pivot_row;
+                    if pivot_rhs {
+                        rhs.toggle(r);
+                    }
+                }
+            }
+            pivots.push(col);
+            row += 1;
+        }
+        (rows, rhs, pivots)
+    }
+
+    /// Number of linearly independent rows
+    pub fn rank(&self) -> usize {
+        self.reduce(&BitVector::new(self.rows.len())).2.len()
+    }
+
+    /// Every `x` with `self * x = 0`, as a basis
+    pub fn null_space(&self) -> Vec<BitVector> {
+        let (rows, _, pivots) = self.reduce(&BitVector::new(self.rows.len()));
+        null_basis(&rows, &pivots, self.cols)
+    }
+
+    /// Solve `self * x = b`.  Returns None if there is no solution.
+    pub fn solve(&self, b: &BitVector) -> Option<Gf2Solution> {
+        assert_eq!(b.len(), self.rows.len(), "need one bit in b for each row");
+        let (rows, rhs, pivots) = self.reduce(b);
+        if (pivots.len()..rows.len()).any(|r| rhs.get(r)) {
+            return None;
+        }
+        let mut particular = BitVector::new(self.cols);
+        for (r, &col) in pivots.iter().enumerate() {
+            particular.set(col, rhs.get(r));
+        }
+        Some(Gf2Solution {
+            particular,
+            null_space: null_basis(&rows, &pivots, self.cols),
+        })
+    }
+}
+
+/// One null space vector per free column: set the free column, and each
+/// pivot column that the free column feeds into
+fn null_basis(rows: &[BitVector], pivots: &[usize], cols: usize) -> Vec<BitVector> {
+    (0..cols)
+        .filter(|c| !pivots.contains(c))
+        .map(|free| {
+            let mut v = BitVector::new(cols);
+            v.set(free, true);
+            for (r, &col) in pivots.iter().enumerate() {
+                if rows[r].get(free) {
+                    v.set(col, true);
+                }
+            }
+            v
+        })
+        .collect()
+}
+
+/// Every solution to a GF(2) system: `particular` XOR any combination of
+/// the `null_space` vectors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gf2Solution {
+    pub particular: BitVector,
+    pub null_space: Vec<BitVector>,
+}
+
+impl Gf2Solution {
+    /// Number of distinct solutions.  Returns None if it doesn't fit a
+    /// `u128`, which needs a null space of 128 or more vectors.
+    pub fn count(&self) -> Option<u128> {
+        1u128.checked_shl(self.null_space.len() as u32)
+    }
+
+    /// Iterate over every solution.  Walks the null space in Gray code
+    /// order, so each step is a single XOR.
+    /// Panics if `count` is None, as there are too many solutions to count.
+    pub fn iter(&self) -> impl Iterator<Item = BitVector> + '_ {
+        let total = self.count().unwrap_or_else(|| {
+            panic!(
+                "{} null space vectors give too many solutions to iterate",
+                self.null_space.len()
+            )
+        });
+        let mut current = self.particular.clone();
+        (0..total).map(move |i| {
+            if i > 0 {
+                current ^= &self.null_space[i.trailing_zeros() as usize];
+            }
+            current.clone()
+        })
+    }
+
+    /// The solution with the fewest set bits.  Searches all `2^k`
+    /// solutions, where `k` is the size of the null space, so panics as
+    /// `iter` does when `count` is None.
+    pub fn min_weight(&self) -> BitVector {
+        self.iter()
+            .min_by_key(|x| x.count_ones())
+            .expect("there is always at least one solution")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lights() {
+        // [.##.] (3) (1,3) (2) (2,3) (0,2) (0,1)
+        let buttons = vec![
+            vec![3],
+            vec![1, 3],
+            vec![2],
+            vec![2, 3],
+            vec![0, 2],
+            vec![0, 1],
+        ];
+        let m = Gf2Matrix::from_columns(4, &buttons);
+        let target = BitVector::from_ones(4, [1, 2]);
+        let solution = m.solve(&target).expect("solvable");
+        assert_eq!(solution.count(), Some(1 << solution.null_space.len()));
+        for x in solution.iter() {
+            let mut lights = BitVector::new(4);
+            for b in x.ones() {
+                for &l in &buttons[b] {
+                    lights.toggle(l);
+                }
+            }
+            assert_eq!(lights, target);
+        }
+        assert_eq!(solution.min_weight().count_ones(), 2);
+    }
+
+    #[test]
+    fn test_large_null_space() {
+        let solution = |k: usize| Gf2Solution {
+            particular: BitVector::new(k),
+            null_space: (0..k).map(|i| BitVector::from_ones(k, [i])).collect(),
+        };
+        let wide = solution(64);
+        assert_eq!(wide.count(), Some(1 << 64));
+        let first: Vec<_> = wide.iter().take(3).map(|x| x.count_ones()).collect();
+        assert_eq!(first, vec![0, 1, 2]);
+        assert_eq!(solution(128).count(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "too many solutions")]
+    fn test_too_many_solutions() {
+        let k = 128;
+        let solution = Gf2Solution {
+            particular: BitVector::new(k),
+            null_space: (0..k).map(|i| BitVector::from_ones(k, [i])).collect(),
+        };
+        solution.min_weight();
+    }
+
+    #[test]
+    fn test_no_solution() {
+        // Both buttons toggle both lights, so only one can be lit
+        let m = Gf2Matrix::from_columns(2, &[vec![0, 1], vec![0, 1]]);
+        assert!(m.solve(&BitVector::from_ones(2, [0])).is_none());
+        assert_eq!(m.rank(), 1);
+    }
+
+    #[test]
+    fn test_null_space() {
+        let m = Gf2Matrix::from_columns(2, &[vec![0], vec![1], vec![0, 1]]);
+        let null = m.null_space();
+        assert_eq!(null, vec![BitVector::from_ones(3, [0, 1, 2])]);
+    }
+}
@@ -0,0 +1,204 @@
+//! A read only grid that borrows the puzzle input, instead of copying it.
+//!
+//! Cells are found with stride arithmetic: each row is `width` bytes plus
+//! its line ending.  Convert to a `Matrix` when the grid needs to change.
+use anyhow::Result;
+
+use crate::{Matrix, MatrixError, Point};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridView<'a> {
+    data: &'a [u8],
+    pub width: usize,
+    pub height: usize,
+    /// Bytes from the start of one row to the start of the next
+    stride: usize,
+}
+
+impl<'a> GridView<'a> {
+    /// View `input` as a grid, with one cell per byte.  Lines may end in
+    /// `\n` or `\r\n`, but must all be the same length.
+    pub fn new(input: &'a str) -> Result<Self, MatrixError> {
+        Self::from_bytes(input.as_bytes())
+    }
+
+    /// View `data` as a grid, with one cell per byte
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self, MatrixError> {
+        let data = data.strip_suffix(b"\n").unwrap_or(data);
+        let first = data.split(|&b| b == b'\n').next().unwrap_or_default();
+        let (width, ending) = match first.strip_suffix(b"\r") {
+            Some(row) => (row.len(), 2),
+            None => (first.len(), 1),
+        };
+        if width == 0 {
+            // Empty only if every line is, otherwise the blank first line is ragged
+            let cells = |row: &'a [u8]| row.strip_suffix(b"\r").unwrap_or(row);
+            return match data
+                .split(|&b| b == b'\n')
+                .map(cells)
+                .find(|row| !row.is_empty())
+            {
+                None => Err(MatrixError::Empty),
+                Some(row) => Err(MatrixError::Ragged {
+                    line: 1,
+                    expected: row.len(),
+                    found: 0,
+                }),
+            };
+        }
+        let stride = width + ending;
+        let mut rows = data.split(|&b| b == b'\n').peekable();
+        let mut height = 0;
+        while let Some(row) = rows.next() {
+            height += 1;
+            // Every line must end the same way as the first, for the stride.
+            // The last line may have lost its ending.
+            let cells = match ending {
+                2 => row.strip_suffix(b"\r"),
+                _ => Some(row),
+            };
+            let ok = match cells {
+                Some(cells) => cells.len() == width,
+                None => rows.peek().is_none() && row.len() == width,
+            };
+            if !ok {
+                return Err(MatrixError::Ragged {
+                    line: height,
+                    expected: width,
+                    found: cells.unwrap_or(row).len(),
+                });
+            }
+        }
+        Ok(Self {
+            data,
+            width,
+            height,
+            stride,
+        })
+    }
+
+    #[inline]
+    pub fn valid_point(&self, point: &Point) -> bool {
+        point.y >= 0
+            && point.y < self.height as isize
+            && point.x >= 0
+            && point.x < self.width as isize
+    }
+
+    pub fn neighbors(&self, p: &Point) -> Vec<Point> {
+        p.neighbors()
+            .into_iter()
+            .filter(|point| self.valid_point(point))
+            .collect()
+    }
+
+    /// Gets a value at position `Point`. Returns Err if the position is not valid.
+    #[inline]
+    pub fn get(&self, point: &Point) -> Result<u8> {
+        match self.valid_point(point) {
+            true => Ok(self.data[self.index(point)]),
+            false => Err(MatrixError::OutOfRange(point.x, point.y).into()),
+        }
+    }
+
+    /// Gets a value at position `Point`. Panics if the position is not valid.
+    #[inline]
+    pub fn get_unsafe(&self, point: &Point) -> u8 {
+        match self.valid_point(point) {
+            true => self.data[self.index(point)],
+            false => panic!("Out of range: [{}:{}]", point.x, point.y),
+        }
+    }
+
+    #[inline]
+    fn index(&self, point: &Point) -> usize {
+        point.y as usize * self.stride + point.x as usize
+    }
+
+    /// The cells of row `y`, without the line ending
+    pub fn row(&self, y: usize) -> &'a [u8] {
+        assert!(y < self.height, "row {} is out of range", y);
+        &self.data[y * self.stride..y * self.stride + self.width]
+    }
+
+    /// The first point, in reading order, holding `value`
+    pub fn find(&self, value: u8) -> Option<Point> {
+        (0..self.height).find_map(|y| {
+            self.row(y)
+                .iter()
+                .position(|&b| b == value)
+                .map(|x| Point::from((x, y)))
+        })
+    }
+
+    /// Copy the grid into a `Matrix`, which can be changed
+    pub fn to_matrix(&self) -> Matrix<u8> {
+        let rows = (0..self.height).map(|y| self.row(y).to_vec()).collect();
+        Matrix::from_rows(rows).expect("a GridView is never empty or ragged")
+    }
+}
+
+impl From<GridView<'_>> for Matrix<u8> {
+    fn from(view: GridView<'_>) -> Self {
+        view.to_matrix()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get() {
+        let view = GridView::new("#.S\n...\n.E#\n").expect("view");
+        assert_eq!((view.width, view.height), (3, 3));
+        assert_eq!(view.get_unsafe(&Point::new(2, 0)), b'S');
+        assert_eq!(view.get(&Point::new(1, 2)).unwrap(), b'E');
+        assert!(view.get(&Point::new(3, 0)).is_err());
+        assert_eq!(view.find(b'E'), Some(Point::new(1, 2)));
+        assert_eq!(view.neighbors(&Point::new(0, 0)).len(), 3);
+    }
+
+    #[test]
+    fn test_crlf() {
+        let view = GridView::new("ab\r\ncd\r\n").expect("view");
+        assert_eq!((view.width, view.height), (2, 2));
+        assert_eq!(view.get_unsafe(&Point::new(0, 1)), b'c');
+        assert_eq!(view.row(1), b"cd");
+        assert!(GridView::new("ab\ncd\r\nef").is_err());
+        assert!(GridView::new("ab\r\ncd\nef").is_err());
+        assert_eq!(GridView::new("abc\r\n").map(|v| v.width), Ok(3));
+    }
+
+    #[test]
+    fn test_bad_shape() {
+        assert_eq!(GridView::new(""), Err(MatrixError::Empty));
+        assert_eq!(GridView::new("\r\n\n"), Err(MatrixError::Empty));
+        assert_eq!(
+            GridView::new("\nabc"),
+            Err(MatrixError::Ragged {
+                line: 1,
+                expected: 3,
+                found: 0
+            })
+        );
+        assert_eq!(
+            GridView::new("abc\nde\nfgh"),
+            Err(MatrixError::Ragged {
+                line: 2,
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_to_matrix() {
+        let input = "#.\n.#";
+        let view = GridView::new(input).expect("view");
+        let mut matrix = view.to_matrix();
+        assert_eq!(matrix, Matrix::<u8>::try_from(input).expect("matrix"));
+        matrix.set_unsafe(&Point::new(1, 0), b'#');
+        assert_eq!(view.get_unsafe(&Point::new(1, 0)), b'.');
+    }
+}
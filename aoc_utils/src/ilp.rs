@@ -0,0 +1,214 @@
+//! Small integer linear programs, such as "fewest button presses".
+//!
+//! Finds non negative integers `x` with `a x = b` that minimize the sum of
+//! `x`.  The system is first reduced with exact Gaussian elimination, so only
+//! the free variables need to be searched.  That search is a branch and bound
+//! over each free variable's range.
+use num::{Integer, Zero};
+
+use crate::{Rational128, linear::reduce};
+
+/// One reduced equation: `scale * x[pivot] = rhs - sum(coef * x[free])`,
+/// scaled so that everything is an integer
+struct Row {
+    pivot: usize,
+    scale: i128,
+    rhs: i128,
+    /// One coefficient per free variable
+    coefs: Vec<i128>,
+}
+
+/// Minimize the sum of `x` such that `a x = b`, and every `x` is a non
+/// negative integer.
+///
+/// Every entry in `a` must be zero or more, which limits each variable to
+/// the smallest `b / a` of the equations it appears in.  This fits puzzles
+/// where each button adds to some counters.  Returns None if there is no
+/// solution.
+///
+/// Panics if `a` has a negative entry.  Use `minimize_sum_bounded` instead.
+pub fn minimize_sum(a: &[Vec<i64>], b: &[i64]) -> Option<Vec<i64>> {
+    assert!(
+        a.iter().flatten().all(|&v| v >= 0),
+        "minimize_sum needs non negative coefficients, use minimize_sum_bounded"
+    );
+    let cols = a.first().map_or(0, |row| row.len());
+    let upper: Vec<i64> = (0..cols)
+        .map(|c| {
+            a.iter()
+                .zip(b)
+                .filter(|(row, _)| row[c] > 0)
+                .map(|(row, v)| v / row[c])
+                .min()
+                .unwrap_or(0)
+        })
+        .collect();
+    minimize_sum_bounded(a, b, &upper)
+}
+
+/// Minimize the sum of `x` such that `a x = b`, and `0 <= x[i] <= upper[i]`
+/// for every variable.  Returns None if there is no solution.
+pub fn minimize_sum_bounded(a: &[Vec<i64>], b: &[i64], upper: &[i64]) -> Option<Vec<i64>> {
+    let cols = a.first().map_or(0, |row| row.len());
+    assert_eq!(upper.len(), cols, "need one upper bound per variable");
+    if upper.iter().any(|&u| u < 0) {
+        return None;
+    }
+
+    let a: Vec<Vec<Rational128>> = a
+        .iter()
+        .map(|row| row.iter().map(|&v| Rational128::from(v as i128)).collect())
+        .collect();
+    let b: Vec<Rational128> = b.iter().map(|&v| Rational128::from(v as i128)).collect();
+    let (m, pivots) = reduce(&a, &b);
+    if m[pivots.len()..].iter().any(|r| !r[cols].is_zero()) {
+        return None;
+    }
+
+    let free: Vec<usize> = (0..cols).filter(|c| !pivots.contains(c)).collect();
+    let rows: Vec<Row> = pivots
+        .iter()
+        .enumerate()
+        .map(|(r, &pivot)| {
+            let scale = m[r].iter().fold(1i128, |acc, v| acc.lcm(v.denom()));
+            let int = |v: &Rational128| (v * scale).to_integer();
+            Row {
+                pivot,
+                scale,
+                rhs: int(&m[r][cols]),
+                coefs: free.iter().map(|&f| int(&m[r][f])).collect(),
+            }
+        })
+        .collect();
+
+    let mut search = Search {
+        rows: &rows,
+        free: &free,
+        upper,
+        values: vec![0; free.len()],
+        best: None,
+    };
+    search.run(0, 0);
+    search.best.map(|(_, x)| x)
+}
+
+struct Search<'a> {
+    rows: &'a [Row],
+    free: &'a [usize],
+    upper: &'a [i64],
+    values: Vec<i128>,
+    best: Option<(i128, Vec<i64>)>,
+}
+
+impl Search<'_> {
+    fn run(&mut self, depth: usize, free_sum: i128) {
+        if self
+            .best
+            .as_ref()
+            .is_some_and(|(best, _)| free_sum >= *best)
+        {
+            return;
+        }
+        if depth == self.free.len() {
+            self.evaluate(free_sum);
+            return;
+        }
+        for v in 0..=self.upper[self.free[depth]] as i128 {
+            self.values[depth] = v;
+            self.run(depth + 1, free_sum + v);
+            if self
+                .best
+                .as_ref()
+                .is_some_and(|(best, _)| free_sum + v >= *best)
+            {
+                break;
+            }
+        }
+        self.values[depth] = 0;
+    }
+
+    /// Work out the pivot variables, now that every free variable is set
+    fn evaluate(&mut self, free_sum: i128) {
+        let mut x = vec![0i64; self.upper.len()];
+        for (&f, &v) in self.free.iter().zip(&self.values) {
+            x[f] = v as i64;
+        }
+        let mut total = free_sum;
+        for row in self.rows {
+            let rest: i128 = row.coefs.iter().zip(&self.values).map(|(c, v)| c * v).sum();
+            let (value, rem) = (row.rhs - rest).div_rem(&row.scale);
+            if rem != 0 || value < 0 || value > self.upper[row.pivot] as i128 {
+                return;
+            }
+            x[row.pivot] = value as i64;
+            total += value;
+        }
+        if self.best.as_ref().is_none_or(|(best, _)| total < *best) {
+            self.best = Some((total, x));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build the matrix for buttons that each add 1 to a list of counters
+    fn buttons(counters: usize, buttons: &[&[usize]]) -> Vec<Vec<i64>> {
+        (0..counters)
+            .map(|c| buttons.iter().map(|b| b.contains(&c) as i64).collect())
+            .collect()
+    }
+
+    /// Check that `x` is a non-negative solution to `a·x == b`
+    fn assert_solves(a: &[Vec<i64>], b: &[i64], x: &[i64]) {
+        assert!(x.iter().all(|&v| v >= 0), "negative press count in {:?}", x);
+        for (row, &target) in a.iter().zip(b) {
+            let total: i64 = row.iter().zip(x).map(|(a, x)| a * x).sum();
+            assert_eq!(total, target, "{:?} does not solve row {:?}", x, row);
+        }
+    }
+
+    #[test]
+    fn test_buttons() {
+        // (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
+        let a = buttons(4, &[&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]]);
+        let x = minimize_sum(&a, &[3, 5, 4, 7]).expect("solution");
+        assert_solves(&a, &[3, 5, 4, 7], &x);
+        assert_eq!(x.iter().sum::<i64>(), 10);
+
+        // (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
+        let a = buttons(
+            5,
+            &[&[0, 2, 3, 4], &[2, 3], &[0, 4], &[0, 1, 2], &[1, 2, 3, 4]],
+        );
+        let x = minimize_sum(&a, &[7, 5, 12, 7, 2]).expect("solution");
+        assert_solves(&a, &[7, 5, 12, 7, 2], &x);
+        assert_eq!(x.iter().sum::<i64>(), 12);
+
+        // (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}
+        let a = buttons(
+            6,
+            &[&[0, 1, 2, 3, 4], &[0, 3, 4], &[0, 1, 2, 4, 5], &[1, 2]],
+        );
+        let x = minimize_sum(&a, &[10, 11, 11, 5, 10, 5]).expect("solution");
+        assert_solves(&a, &[10, 11, 11, 5, 10, 5], &x);
+        assert_eq!(x.iter().sum::<i64>(), 11);
+    }
+
+    #[test]
+    fn test_no_solution() {
+        // One button adds 2, and the target is odd
+        assert_eq!(minimize_sum(&[vec![2]], &[3]), None);
+    }
+
+    #[test]
+    fn test_bounded() {
+        // x + y = 10, with x limited to 3
+        let x = minimize_sum_bounded(&[vec![1, 1]], &[10], &[3, 10]).expect("solution");
+        assert_eq!(x.iter().sum::<i64>(), 10);
+        assert!(x[0] <= 3);
+        assert_solves(&[vec![1, 1]], &[10], &x);
+        assert_eq!(minimize_sum_bounded(&[vec![1, 1]], &[10], &[3, 5]), None);
+    }
+}
@@ -0,0 +1,117 @@
+//! Puzzle input, split into sections and lines.
+//!
+//! Line endings are normalized to `\n` and trailing whitespace is removed,
+//! so CRLF files split the same way as LF files.
+use anyhow::Result;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::{Matrix, MatrixError, all_integers, data_to_grid, data_to_numbers};
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Input {
+    text: String,
+}
+
+impl Input {
+    /// Normalize `raw`: CRLF becomes LF, each line loses its trailing
+    /// whitespace, and blank lines at the end are dropped
+    pub fn new(raw: &str) -> Self {
+        let lines: Vec<&str> = raw.lines().map(str::trim_end).collect();
+        let end = lines
+            .iter()
+            .rposition(|line| !line.is_empty())
+            .map_or(0, |i| i + 1);
+        Self {
+            text: lines[..end].join("\n"),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// The parts of the input between blank lines.  A run of blank lines
+    /// counts as one separator.
+    pub fn sections(&self) -> Vec<Input> {
+        self.text
+            .split("\n\n")
+            .map(|section| section.trim_matches('\n'))
+            .filter(|section| !section.is_empty())
+            .map(Input::new)
+            .collect()
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.text.lines()
+    }
+
+    /// One cell per char, as `data_to_grid`
+    pub fn grid<T: TryFrom<char>>(&self) -> Result<Vec<Vec<T>>> {
+        data_to_grid(&self.text)
+    }
+
+    pub fn matrix<T: TryFrom<char> + Copy>(&self) -> Result<Matrix<T>, MatrixError> {
+        Matrix::try_from(self.text.as_str())
+    }
+
+    /// The numbers on each line, separated by whitespace or commas
+    pub fn numbers<T: FromStr>(&self) -> Result<Vec<Vec<T>>> {
+        data_to_numbers(&self.text, |c: char| c.is_whitespace() || c == ',')
+    }
+
+    /// Every signed integer on each line, skipping any other text
+    pub fn ints(&self) -> Vec<Vec<i64>> {
+        self.lines().map(all_integers).collect()
+    }
+}
+
+impl From<&str> for Input {
+    fn from(raw: &str) -> Self {
+        Self::new(raw)
+    }
+}
+
+impl Display for Input {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let input = Input::new("#.# \r\n..#\t\r\n\r\n\r\n");
+        assert_eq!(input.as_str(), "#.#\n..#");
+        assert_eq!(input.lines().count(), 2);
+    }
+
+    #[test]
+    fn test_sections() {
+        let input = Input::new("##\r\n.#\r\n\r\n \r\n<^>\r\nv\r\n");
+        let sections = input.sections();
+        assert_eq!(sections.len(), 2);
+        let grid = sections[0].grid::<char>().expect("grid");
+        assert_eq!(grid, vec![vec!['#', '#'], vec!['.', '#']]);
+        assert_eq!(sections[1].lines().collect::<Vec<_>>(), ["<^>", "v"]);
+    }
+
+    #[test]
+    fn test_numbers() {
+        let input = Input::new("47|53\n\n75,47,61\n97, 13");
+        let sections = input.sections();
+        assert_eq!(sections[0].ints(), vec![vec![47, 53]]);
+        let updates = sections[1].numbers::<u32>().expect("numbers");
+        assert_eq!(updates, vec![vec![75, 47, 61], vec![97, 13]]);
+        assert!(sections[0].numbers::<u32>().is_err());
+    }
+
+    #[test]
+    fn test_matrix() {
+        let matrix = Input::new("..\r\n#.\r\n").matrix::<u8>().expect("matrix");
+        assert_eq!((matrix.width, matrix.height), (2, 2));
+    }
+}
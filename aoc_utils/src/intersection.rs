@@ -0,0 +1,299 @@
+//! Exact intersection of rays and segments, in 2D and 3D.
+//!
+//! Everything is computed with `i128`, and crossing points are returned as
+//! exact fractions, so there is no floating point rounding to worry about.
+use num::rational::Ratio;
+use num::traits::NumCast;
+
+use crate::{Coord, Point, Point3};
+
+pub type Rational128 = Ratio<i128>;
+
+/// How two paths relate to each other
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Crossing<P> {
+    /// The paths cross at `at`.  The first path gets there at time `t`, and the
+    /// second at time `u`.  Both times are zero or later.
+    Future {
+        at: P,
+        t: Rational128,
+        u: Rational128,
+    },
+    /// The lines cross, but one or both paths would have been there in the past
+    Past {
+        at: P,
+        t: Rational128,
+        u: Rational128,
+    },
+    /// The paths run side by side, and never meet
+    Parallel,
+    /// The paths lie on the same line
+    Collinear,
+    /// The paths are neither parallel nor crossing (3D only)
+    Skew,
+}
+
+impl<P> Crossing<P> {
+    fn at(at: P, t: Rational128, u: Rational128) -> Self {
+        match t >= Rational128::from(0) && u >= Rational128::from(0) {
+            true => Self::Future { at, t, u },
+            false => Self::Past { at, t, u },
+        }
+    }
+
+    /// The crossing point, if the paths cross in the future
+    pub fn future_point(&self) -> Option<&P> {
+        match self {
+            Self::Future { at, .. } => Some(at),
+            _ => None,
+        }
+    }
+}
+
+impl Crossing<[Rational128; 2]> {
+    /// True if the paths cross in the future, inside the square from `min` to `max`
+    pub fn future_within(&self, min: i128, max: i128) -> bool {
+        let (min, max) = (Rational128::from(min), Rational128::from(max));
+        self.future_point()
+            .is_some_and(|at| at.iter().all(|v| *v >= min && *v <= max))
+    }
+}
+
+fn to_i128<T: Coord>(v: T) -> i128 {
+    NumCast::from(v).expect("coordinate does not fit in i128")
+}
+
+#[inline]
+fn cross2(a: [i128; 2], b: [i128; 2]) -> i128 {
+    a[0] * b[1] - a[1] * b[0]
+}
+
+#[inline]
+fn cross3(a: [i128; 3], b: [i128; 3]) -> [i128; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+#[inline]
+fn dot3(a: [i128; 3], b: [i128; 3]) -> i128 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// A 2D path that starts at `pos` and moves by `vel` each time step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ray2 {
+    pub pos: [i128; 2],
+    pub vel: [i128; 2],
+}
+
+impl Ray2 {
+    pub fn new<T: Coord>(pos: Point<T>, vel: Point<T>) -> Self {
+        Self {
+            pos: [to_i128(pos.x), to_i128(pos.y)],
+            vel: [to_i128(vel.x), to_i128(vel.y)],
+        }
+    }
+
+    /// Position at time `t`
+    pub fn at(&self, t: Rational128) -> [Rational128; 2] {
+        [0, 1].map(|i| Rational128::from(self.pos[i]) + t * self.vel[i])
+    }
+
+    /// Where, and when, this path crosses `other`
+    pub fn crossing(&self, other: &Self) -> Crossing<[Rational128; 2]> {
+        let w = [other.pos[0] - self.pos[0], other.pos[1] - self.pos[1]];
+        let d = cross2(self.vel, other.vel);
+        if d == 0 {
+            return match cross2(w, self.vel) == 0 {
+                true => Crossing::Collinear,
+                false => Crossing::Parallel,
+            };
+        }
+        let t = Rational128::new(cross2(w, other.vel), d);
+        let u = Rational128::new(cross2(w, self.vel), d);
+        Crossing::at(self.at(t), t, u)
+    }
+}
+
+/// A 3D path that starts at `pos` and moves by `vel` each time step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ray3 {
+    pub pos: [i128; 3],
+    pub vel: [i128; 3],
+}
+
+impl Ray3 {
+    pub fn new(pos: Point3, vel: Point3) -> Self {
+        Self {
+            pos: [pos.x, pos.y, pos.z].map(to_i128),
+            vel: [vel.x, vel.y, vel.z].map(to_i128),
+        }
+    }
+
+    /// Position at time `t`
+    pub fn at(&self, t: Rational128) -> [Rational128; 3] {
+        [0, 1, 2].map(|i| Rational128::from(self.pos[i]) + t * self.vel[i])
+    }
+
+    /// Where, and when, this path crosses `other`
+    pub fn crossing(&self, other: &Self) -> Crossing<[Rational128; 3]> {
+        let w = [0, 1, 2].map(|i| other.pos[i] - self.pos[i]);
+        let n = cross3(self.vel, other.vel);
+        if n == [0; 3] {
+            return match cross3(w, self.vel) == [0; 3] {
+                true => Crossing::Collinear,
+                false => Crossing::Parallel,
+            };
+        }
+        if dot3(w, n) != 0 {
+            return Crossing::Skew;
+        }
+        let nn = dot3(n, n);
+        let t = Rational128::new(dot3(cross3(w, other.vel), n), nn);
+        let u = Rational128::new(dot3(cross3(w, self.vel), n), nn);
+        Crossing::at(self.at(t), t, u)
+    }
+}
+
+/// A horizontal or vertical line segment, including both ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Segment {
+    pub start: Point,
+    pub end: Point,
+}
+
+impl Segment {
+    /// Create a segment.  Panics if it is neither horizontal nor vertical.
+    pub fn new(start: Point, end: Point) -> Self {
+        assert!(
+            start.x == end.x || start.y == end.y,
+            "Segment {}-{} is not axis aligned",
+            start,
+            end
+        );
+        Self { start, end }
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        self.start.y == self.end.y
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        self.start.x == self.end.x
+    }
+
+    /// Number of steps from one end to the other
+    pub fn len(&self) -> isize {
+        self.start.manhattan(&self.end)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// True if `p` lies on the segment
+    pub fn contains(&self, p: &Point) -> bool {
+        let (min, max) = self.bounds();
+        p.x >= min.x && p.x <= max.x && p.y >= min.y && p.y <= max.y
+    }
+
+    fn bounds(&self) -> (Point, Point) {
+        (
+            Point {
+                x: self.start.x.min(self.end.x),
+                y: self.start.y.min(self.end.y),
+            },
+            Point {
+                x: self.start.x.max(self.end.x),
+                y: self.start.y.max(self.end.y),
+            },
+        )
+    }
+
+    /// The part shared by both segments.  Crossing segments share a single
+    /// point, where `start == end`.  Returns None if they don't touch.
+    pub fn overlap(&self, other: &Self) -> Option<Segment> {
+        let (a_min, a_max) = self.bounds();
+        let (b_min, b_max) = other.bounds();
+        let min = Point {
+            x: a_min.x.max(b_min.x),
+            y: a_min.y.max(b_min.y),
+        };
+        let max = Point {
+            x: a_max.x.min(b_max.x),
+            y: a_max.y.min(b_max.y),
+        };
+        match min.x <= max.x && min.y <= max.y {
+            true => Some(Segment {
+                start: min,
+                end: max,
+            }),
+            false => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hailstone(p: [isize; 3], v: [isize; 3]) -> Ray2 {
+        Ray2::new(Point::new(p[0], p[1]), Point::new(v[0], v[1]))
+    }
+
+    #[test]
+    fn test_ray2() {
+        // From the 2023 day 24 example
+        let a = hailstone([19, 13, 30], [-2, 1, -2]);
+        let b = hailstone([18, 19, 22], [-1, -1, -2]);
+        let c = hailstone([20, 25, 34], [-2, -2, -4]);
+        let d = hailstone([20, 19, 15], [1, -5, -3]);
+
+        let ab = a.crossing(&b);
+        assert_eq!(
+            ab.future_point(),
+            Some(&[Rational128::new(43, 3), Rational128::new(46, 3)])
+        );
+        assert!(ab.future_within(7, 27));
+        assert_eq!(b.crossing(&c), Crossing::Parallel);
+        assert!(matches!(a.crossing(&d), Crossing::Past { .. }));
+        assert_eq!(b.crossing(&b), Crossing::Collinear);
+    }
+
+    #[test]
+    fn test_ray3() {
+        let a = Ray3::new(Point3::new(0, 0, 0), Point3::new(1, 1, 1));
+        let b = Ray3::new(Point3::new(4, 0, 0), Point3::new(-1, 1, 1));
+        match a.crossing(&b) {
+            Crossing::Future { at, t, u } => {
+                assert_eq!(at, [Rational128::from(2); 3]);
+                assert_eq!((t, u), (Rational128::from(2), Rational128::from(2)));
+            }
+            other => panic!("expected a crossing, got {:?}", other),
+        }
+        let c = Ray3::new(Point3::new(0, 0, 5), Point3::new(-1, 1, 0));
+        assert_eq!(a.crossing(&c), Crossing::Skew);
+        let d = Ray3::new(Point3::new(0, 1, 0), Point3::new(2, 2, 2));
+        assert_eq!(a.crossing(&d), Crossing::Parallel);
+    }
+
+    #[test]
+    fn test_segment_overlap() {
+        let h = Segment::new(Point::new(0, 5), Point::new(10, 5));
+        let v = Segment::new(Point::new(3, 0), Point::new(3, 8));
+        let crossing = h.overlap(&v).unwrap();
+        assert!(crossing.is_empty());
+        assert_eq!(crossing.start, Point::new(3, 5));
+
+        let h2 = Segment::new(Point::new(12, 5), Point::new(6, 5));
+        assert_eq!(
+            h.overlap(&h2),
+            Some(Segment::new(Point::new(6, 5), Point::new(10, 5)))
+        );
+        let v2 = Segment::new(Point::new(11, 0), Point::new(11, 8));
+        assert_eq!(h.overlap(&v2), None);
+    }
+}
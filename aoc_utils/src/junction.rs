@@ -0,0 +1,402 @@
+//! Compress a maze into a weighted graph of its junctions, and search it.
+//!
+//! Corridors between branch points become single weighted edges, so a DFS
+//! only has to branch at the junctions.  Visited sets are tracked as bitsets.
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::{Matrix, Point};
+
+/// How often (in DFS calls) the time budget is checked
+const BUDGET_CHECK_INTERVAL: usize = 1 << 12;
+
+/// What a search found, and whether its time budget ran out first.
+/// If `timed_out` is true, `value` is only the best found so far.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult<T> {
+    pub value: T,
+    pub timed_out: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JunctionGraph {
+    /// Position of each node in the original grid
+    pub nodes: Vec<Point>,
+    /// Directed, weighted edges: `edges[from]` is a list of `(to, steps)`
+    pub edges: Vec<Vec<(usize, usize)>>,
+    index: HashMap<Point, usize>,
+}
+
+impl JunctionGraph {
+    /// Build a junction graph from a grid.
+    ///
+    /// `passable(from, to)` returns true if a single step from `from` to the
+    /// adjacent point `to` is allowed.  This makes one-way cells (such as
+    /// slopes) possible.  `start` and `end` are always nodes, as is every
+    /// cell with three or more connected neighbors.  Dead ends are dropped.
+    pub fn from_matrix<T, F>(matrix: &Matrix<T>, start: &Point, end: &Point, passable: F) -> Self
+    where
+        T: Copy,
+        F: Fn(&Point, &Point) -> bool,
+    {
+        let connected = |p: &Point| -> Vec<Point> {
+            p.neighbors_hv()
+                .into_iter()
+                .filter(|q| matrix.valid_point(q) && (passable(p, q) || passable(q, p)))
+                .collect()
+        };
+
+        let mut nodes = vec![*start, *end];
+        for y in 0..matrix.height {
+            for x in 0..matrix.width {
+                let p = Point::from((x, y));
+                if p != *start && p != *end && connected(&p).len() > 2 {
+                    nodes.push(p);
+                }
+            }
+        }
+        let index: HashMap<Point, usize> = nodes.iter().enumerate().map(|(i, p)| (*p, i)).collect();
+
+        let mut edges = vec![Vec::new(); nodes.len()];
+        for (from, node) in nodes.iter().enumerate() {
+            for first in connected(node) {
+                let mut forward = passable(node, &first);
+                let mut prev = *node;
+                let mut cur = first;
+                let mut steps = 1;
+                let to = loop {
+                    if let Some(&to) = index.get(&cur) {
+                        break Some(to);
+                    }
+                    let next: Vec<Point> =
+                        connected(&cur).into_iter().filter(|q| *q != prev).collect();
+                    if next.len() != 1 {
+                        break None;
+                    }
+                    forward &= passable(&cur, &next[0]);
+                    prev = cur;
+                    cur = next[0];
+                    steps += 1;
+                };
+                let Some(to) = to else { continue };
+                if !forward || to == from {
+                    continue;
+                }
+                edges[from].push((to, steps));
+            }
+        }
+
+        Self {
+            nodes,
+            edges,
+            index,
+        }
+    }
+
+    /// Build an undirected junction graph, where `open(cell)` is true for any
+    /// cell that can be walked on.
+    pub fn from_open_cells<T, F>(matrix: &Matrix<T>, start: &Point, end: &Point, open: F) -> Self
+    where
+        T: Copy,
+        F: Fn(T) -> bool,
+    {
+        Self::from_matrix(matrix, start, end, |a, b| {
+            open(matrix.get_unsafe(a)) && open(matrix.get_unsafe(b))
+        })
+    }
+
+    /// Returns the node index for a grid position, if it is a node
+    pub fn node(&self, point: &Point) -> Option<usize> {
+        self.index.get(point).copied()
+    }
+
+    /// Length of the longest simple path from `from` to `to`.
+    ///
+    /// If `budget` is given, the search stops once it is exhausted and the
+    /// best length found so far is returned, with `timed_out` set.
+    pub fn longest_path(
+        &self,
+        from: &Point,
+        to: &Point,
+        budget: Option<Duration>,
+    ) -> SearchResult<Option<usize>> {
+        let (Some(from), Some(to)) = (self.node(from), self.node(to)) else {
+            return SearchResult {
+                value: None,
+                timed_out: false,
+            };
+        };
+        let mut search = Search::new(self, to, budget);
+        // Every node still to be visited arrives over one of its edges.
+        let best_in: Vec<usize> = (0..self.nodes.len())
+            .map(|n| {
+                self.edges
+                    .iter()
+                    .flat_map(|e| e.iter().filter(|(t, _)| *t == n).map(|(_, w)| *w))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let remaining = best_in.iter().sum::<usize>() - best_in[from];
+        search.visited.insert(from);
+        search.longest(from, 0, remaining, &best_in);
+        SearchResult {
+            value: search.best,
+            timed_out: search.timed_out,
+        }
+    }
+
+    /// Every simple path from `from` to `to`, as the list of node positions
+    /// and the total number of steps.
+    ///
+    /// If `budget` is given, the search stops once it is exhausted and the
+    /// paths found so far are returned, with `timed_out` set.
+    pub fn all_simple_paths(
+        &self,
+        from: &Point,
+        to: &Point,
+        budget: Option<Duration>,
+    ) -> SearchResult<Vec<(Vec<Point>, usize)>> {
+        let (Some(from), Some(to)) = (self.node(from), self.node(to)) else {
+            return SearchResult {
+                value: Vec::new(),
+                timed_out: false,
+            };
+        };
+        let mut search = Search::new(self, to, budget);
+        let mut path = vec![from];
+        search.visited.insert(from);
+        search.all(from, 0, &mut path);
+        SearchResult {
+            value: search.paths,
+            timed_out: search.timed_out,
+        }
+    }
+
+    /// If `to` has a single way in, any path that reaches that node has to
+    /// take it straight away.
+    fn forced_exit(&self, to: usize) -> Option<usize> {
+        let mut preds = self
+            .edges
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.iter().any(|(t, _)| *t == to))
+            .map(|(n, _)| n);
+        match (preds.next(), preds.next()) {
+            (Some(n), None) => Some(n),
+            _ => None,
+        }
+    }
+}
+
+/// A fixed size bitset of visited nodes
+#[derive(Debug, Clone)]
+struct Visited(Vec<u64>);
+
+impl Visited {
+    fn new(len: usize) -> Self {
+        Self(vec![0; len.div_ceil(64)])
+    }
+
+    #[inline]
+    fn contains(&self, n: usize) -> bool {
+        self.0[n / 64] & (1 << (n % 64)) != 0
+    }
+
+    #[inline]
+    fn insert(&mut self, n: usize) {
+        self.0[n / 64] |= 1 << (n % 64);
+    }
+
+    #[inline]
+    fn remove(&mut self, n: usize) {
+        self.0[n / 64] &= !(1 << (n % 64));
+    }
+}
+
+struct Search<'a> {
+    graph: &'a JunctionGraph,
+    to: usize,
+    forced: Option<usize>,
+    visited: Visited,
+    started: Instant,
+    budget: Option<Duration>,
+    calls: usize,
+    timed_out: bool,
+    best: Option<usize>,
+    paths: Vec<(Vec<Point>, usize)>,
+}
+
+impl<'a> Search<'a> {
+    fn new(graph: &'a JunctionGraph, to: usize, budget: Option<Duration>) -> Self {
+        Self {
+            graph,
+            to,
+            forced: graph.forced_exit(to),
+            visited: Visited::new(graph.nodes.len()),
+            started: Instant::now(),
+            budget,
+            calls: 0,
+            timed_out: false,
+            best: None,
+            paths: Vec::new(),
+        }
+    }
+
+    fn out_of_time(&mut self) -> bool {
+        self.calls += 1;
+        if !self.timed_out
+            && self.calls.is_multiple_of(BUDGET_CHECK_INTERVAL)
+            && let Some(budget) = self.budget
+        {
+            self.timed_out = self.started.elapsed() >= budget;
+        }
+        self.timed_out
+    }
+
+    /// The edges worth following from `node`
+    fn next(&self, node: usize) -> impl Iterator<Item = &'a (usize, usize)> + use<'a> {
+        let forced = self.forced == Some(node);
+        let to = self.to;
+        self.graph.edges[node]
+            .iter()
+            .filter(move |(next, _)| !forced || *next == to)
+    }
+
+    fn longest(&mut self, node: usize, length: usize, remaining: usize, best_in: &[usize]) {
+        if node == self.to {
+            self.best = self.best.max(Some(length));
+            return;
+        }
+        if self.out_of_time() {
+            return;
+        }
+        if let Some(best) = self.best
+            && length + remaining <= best
+        {
+            return;
+        }
+        for &(next, steps) in self.next(node) {
+            if self.visited.contains(next) {
+                continue;
+            }
+            self.visited.insert(next);
+            self.longest(next, length + steps, remaining - best_in[next], best_in);
+            self.visited.remove(next);
+        }
+    }
+
+    fn all(&mut self, node: usize, length: usize, path: &mut Vec<usize>) {
+        if node == self.to {
+            let points = path.iter().map(|&n| self.graph.nodes[n]).collect();
+            self.paths.push((points, length));
+            return;
+        }
+        if self.out_of_time() {
+            return;
+        }
+        for &(next, steps) in self.next(node) {
+            if self.visited.contains(next) {
+                continue;
+            }
+            self.visited.insert(next);
+            path.push(next);
+            self.all(next, length + steps, path);
+            path.pop();
+            self.visited.remove(next);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static MAZE: &str = "\
+#.#####
+#.....#
+#.###.#
+#.....#
+#####.#";
+
+    fn graph() -> (JunctionGraph, Point, Point) {
+        let matrix = MAZE.parse::<Matrix<u8>>().expect("Matrix failed to parse!");
+        let start = Point::from((1, 0));
+        let end = Point::from((5, 4));
+        let graph = JunctionGraph::from_open_cells(&matrix, &start, &end, |c| c != b'#');
+        (graph, start, end)
+    }
+
+    #[test]
+    fn test_compress() {
+        let (graph, start, end) = graph();
+        // start, end, and the two junctions at [1,1] and [3,5]
+        assert_eq!(graph.nodes.len(), 4);
+        assert!(graph.node(&start).is_some());
+        assert!(graph.node(&end).is_some());
+        assert!(graph.node(&Point::from((1, 1))).is_some());
+        assert!(graph.node(&Point::from((5, 3))).is_some());
+    }
+
+    #[test]
+    fn test_longest_path() {
+        let (graph, start, end) = graph();
+        let result = graph.longest_path(&start, &end, None);
+        assert_eq!(result.value, Some(8));
+        assert!(!result.timed_out);
+    }
+
+    #[test]
+    fn test_all_simple_paths() {
+        let (graph, start, end) = graph();
+        let mut lengths: Vec<usize> = graph
+            .all_simple_paths(&start, &end, None)
+            .value
+            .into_iter()
+            .map(|(_, length)| length)
+            .collect();
+        lengths.sort();
+        assert_eq!(lengths, vec![8, 8]);
+    }
+
+    #[test]
+    fn test_one_way() {
+        let input = "#.###\n#.>.#\n###.#";
+        let matrix = input
+            .parse::<Matrix<u8>>()
+            .expect("Matrix failed to parse!");
+        let start = Point::from((1, 0));
+        let end = Point::from((3, 2));
+        let passable = |a: &Point, b: &Point| match matrix.get_unsafe(b) {
+            b'#' => false,
+            b'>' => b.x > a.x,
+            _ => true,
+        };
+        let graph = JunctionGraph::from_matrix(&matrix, &start, &end, passable);
+        assert_eq!(graph.longest_path(&start, &end, None).value, Some(4));
+        assert_eq!(graph.longest_path(&end, &start, None).value, None);
+    }
+
+    #[test]
+    fn test_budget() {
+        // Every cell of an open grid is a junction, so the search runs far
+        // past the check interval
+        let matrix = vec![".".repeat(8); 8]
+            .join("\n")
+            .parse::<Matrix<u8>>()
+            .expect("matrix");
+        let (start, end) = (Point::from((0, 0)), Point::from((7, 7)));
+        let open = JunctionGraph::from_open_cells(&matrix, &start, &end, |c| c != b'#');
+        let result = open.longest_path(&start, &end, Some(Duration::ZERO));
+        assert!(result.timed_out);
+        assert!(
+            open.all_simple_paths(&start, &end, Some(Duration::ZERO))
+                .timed_out
+        );
+
+        let (graph, start, end) = graph();
+        let result = graph.longest_path(&start, &end, Some(Duration::ZERO));
+        assert_eq!(result.value, Some(8));
+        assert!(!result.timed_out);
+    }
+}
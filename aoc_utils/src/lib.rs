@@ -1,24 +1,66 @@
-#![feature(pattern)]
+#![cfg_attr(feature = "nightly", feature(pattern))]
+
+// Lets the derive macros refer to `::aoc_utils` from inside this crate
+extern crate self as aoc_utils;
 
 pub mod aoc;
 pub mod convert;
 pub mod dag;
 pub mod digits;
 pub mod direction;
+pub mod geometry;
+pub mod gf2;
+pub mod grid_view;
+pub mod ilp;
+pub mod input;
+pub mod intersection;
+pub mod junction;
+pub mod linear;
+pub mod location;
 pub mod matrix;
+pub mod matrix3;
+pub mod number_theory;
 pub mod parser;
 pub mod point;
+pub mod point3;
+pub mod point_n;
+pub mod pose;
+pub mod range_set;
+pub mod scan;
+pub mod separator;
+pub mod sequence;
 
 pub use aoc::*;
 pub use convert::*;
 pub use dag::*;
 pub use digits::*;
 pub use direction::*;
+pub use geometry::*;
+pub use gf2::*;
+pub use grid_view::*;
+pub use ilp::*;
+// Named, so that it wins over `nom::Input`
+pub use input::Input;
+pub use intersection::*;
+pub use junction::*;
+pub use linear::*;
+pub use location::*;
 pub use matrix::*;
+pub use matrix3::*;
+pub use number_theory::*;
 pub use parser::*;
 pub use point::*;
+pub use point_n::*;
+pub use point3::*;
+pub use pose::*;
+pub use range_set::*;
+pub use scan::*;
+pub use separator::*;
+pub use sequence::*;
 
 // Re-exports
+pub use aoc_derive::FromLine;
+
 pub extern crate nom;
 pub use nom::*;
 
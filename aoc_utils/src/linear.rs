@@ -0,0 +1,180 @@
+//! Exact solver for systems of linear equations.
+//!
+//! Gaussian elimination over exact fractions, so there is no floating point
+//! error when checking whether a solution is a whole number.  Use
+//! `Ratio<i128>` for small systems, and `BigRational` when the coefficients
+//! are large enough that elimination could overflow (such as hailstones).
+use num::rational::Ratio;
+use num::{Integer, Num, Signed};
+
+/// The result of solving `A x = b`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solution<T> {
+    /// Exactly one `x` solves the system
+    Unique(Vec<T>),
+    /// The equations contradict each other
+    None,
+    /// There are infinitely many solutions.  `particular` is one of them, with
+    /// every variable in `free` set to zero.
+    Infinite {
+        particular: Vec<T>,
+        free: Vec<usize>,
+    },
+}
+
+impl<T> Solution<T> {
+    /// The solution, if there is exactly one
+    pub fn unique(self) -> Option<Vec<T>> {
+        match self {
+            Self::Unique(x) => Some(x),
+            _ => None,
+        }
+    }
+}
+
+impl<I: Integer + Clone> Solution<Ratio<I>> {
+    /// The solution, if there is exactly one, and every value is a whole number
+    pub fn integer(&self) -> Option<Vec<I>> {
+        match self {
+            Self::Unique(x) => x
+                .iter()
+                .map(|v| v.is_integer().then(|| v.to_integer()))
+                .collect(),
+            _ => None,
+        }
+    }
+}
+
+/// Solve `a x = b`, where `a` has one row per equation and one column per
+/// variable.  Any number of equations and variables is allowed.
+///
+/// Panics if the rows of `a` are not all the same length, or if `b` does
+/// not have one value per row.
+pub fn solve<T: Clone + Num>(a: &[Vec<T>], b: &[T]) -> Solution<T> {
+    let (m, pivots) = reduce(a, b);
+    let cols = m.first().map_or(0, |row| row.len() - 1);
+
+    // Any row without a pivot reads 0 = b, which must hold
+    if m[pivots.len()..].iter().any(|r| !r[cols].is_zero()) {
+        return Solution::None;
+    }
+
+    let mut x = vec![T::zero(); cols];
+    for (r, &col) in pivots.iter().enumerate() {
+        x[col] = m[r][cols].clone();
+    }
+    match pivots.len() == cols {
+        true => Solution::Unique(x),
+        false => Solution::Infinite {
+            particular: x,
+            free: (0..cols).filter(|c| !pivots.contains(c)).collect(),
+        },
+    }
+}
+
+/// Reduce the augmented matrix `[a | b]` to reduced row echelon form.
+/// Returns the matrix, and the pivot column of each of the leading rows.
+pub(crate) fn reduce<T: Clone + Num>(a: &[Vec<T>], b: &[T]) -> (Vec<Vec<T>>, Vec<usize>) {
+    assert_eq!(a.len(), b.len(), "need one value in b for each row of a");
+    let cols = a.first().map_or(0, |row| row.len());
+    assert!(
+        a.iter().all(|row| row.len() == cols),
+        "rows of a are not all the same length"
+    );
+
+    let mut m: Vec<Vec<T>> = a
+        .iter()
+        .zip(b)
+        .map(|(row, v)| row.iter().cloned().chain([v.clone()]).collect())
+        .collect();
+
+    let mut pivots = Vec::new();
+    let mut row = 0;
+    for col in 0..cols {
+        if row == m.len() {
+            break;
+        }
+        let Some(p) = (row..m.len()).find(|&r| !m[r][col].is_zero()) else {
+            continue;
+        };
+        m.swap(row, p);
+        let pivot = m[row][col].clone();
+        for v in m[row].iter_mut() {
+            *v = v.clone() / pivot.clone();
+        }
+        let pivot_row = m[row].clone();
+        for (r, other) in m.iter_mut().enumerate() {
+            if r == row || other[col].is_zero() {
+                continue;
+            }
+            let factor = other[col].clone();
+            for (v, p) in other[col..].iter_mut().zip(&pivot_row[col..]) {
+                *v = v.clone() - factor.clone() * p.clone();
+            }
+        }
+        pivots.push(col);
+        row += 1;
+    }
+    (m, pivots)
+}
+
+/// Solve `a x = b` for integer coefficients, over exact fractions.
+/// Works for `i128` as well as `BigInt`.
+pub fn solve_integer<I: Integer + Signed + Clone>(a: &[Vec<I>], b: &[I]) -> Solution<Ratio<I>> {
+    let a: Vec<Vec<Ratio<I>>> = a
+        .iter()
+        .map(|row| row.iter().cloned().map(Ratio::from_integer).collect())
+        .collect();
+    let b: Vec<Ratio<I>> = b.iter().cloned().map(Ratio::from_integer).collect();
+    solve(&a, &b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::BigInt;
+
+    #[test]
+    fn test_claw_machine() {
+        // Button A: X+94, Y+34; Button B: X+22, Y+67; Prize: X=8400, Y=5400
+        let solution = solve_integer(&[vec![94i128, 22], vec![34, 67]], &[8400, 5400]);
+        assert_eq!(solution.integer(), Some(vec![80, 40]));
+
+        // Button A: X+26, Y+66; Button B: X+67, Y+21; Prize: X=12748, Y=12176
+        let solution = solve_integer(&[vec![26i128, 67], vec![66, 21]], &[12748, 12176]);
+        assert!(matches!(solution, Solution::Unique(_)));
+        assert_eq!(solution.integer(), None);
+    }
+
+    #[test]
+    fn test_no_solution() {
+        let solution = solve_integer(&[vec![1i128, 1], vec![2, 2]], &[1, 3]);
+        assert_eq!(solution, Solution::None);
+    }
+
+    #[test]
+    fn test_infinite() {
+        let solution = solve_integer(&[vec![1i128, 1], vec![2, 2]], &[2, 4]);
+        assert_eq!(
+            solution,
+            Solution::Infinite {
+                particular: vec![Ratio::from_integer(2), Ratio::from_integer(0)],
+                free: vec![1],
+            }
+        );
+    }
+
+    #[test]
+    fn test_big() {
+        let big = |v: i64| BigInt::from(v) * BigInt::from(1_000_000_000_000i64);
+        let a = vec![
+            vec![big(3), big(2), big(-1)],
+            vec![big(2), big(-2), big(4)],
+            vec![big(-1), big(1), big(-1)],
+        ];
+        let b = vec![big(1), big(-2), big(-1)];
+        let x = solve_integer(&a, &b).unique().expect("unique solution");
+        let expected = [1, -2, -2].map(|v| Ratio::from_integer(BigInt::from(v)));
+        assert_eq!(x, expected);
+    }
+}
@@ -0,0 +1,128 @@
+//! Where in the input a parse went wrong, for error messages.
+use std::fmt::Display;
+
+/// A position in the input, with the text found there and what was
+/// expected instead.  Lines and columns count from 1, and columns count
+/// chars rather than bytes.
+///
+/// Displays as a message, followed by the input line with a caret under
+/// the offending text:
+/// ```text
+/// line 2, column 3: expected a digit, found "x"
+///   |
+/// 2 | 12x4
+///   |   ^
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+    /// The whole input line
+    pub source: String,
+    pub found: String,
+    pub expected: String,
+}
+
+impl Location {
+    /// The location of the `len` bytes at byte `offset` in `input`
+    pub fn at_offset(input: &str, offset: usize, len: usize, expected: impl Into<String>) -> Self {
+        let offset = offset.min(input.len());
+        let start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+        let source = input[start..end].trim_end_matches('\r');
+        let found_end = (offset + len).min(end).max(offset);
+        Self {
+            line: input[..start].matches('\n').count() + 1,
+            column: input[start..offset].chars().count() + 1,
+            source: source.to_string(),
+            found: input[offset..found_end].to_string(),
+            expected: expected.into(),
+        }
+    }
+
+    /// The location of `rest`, which must be the tail of `input`, as nom
+    /// leaves it.  The found text runs to the next whitespace, and is at
+    /// least one char.
+    pub fn at_rest(input: &str, rest: &str, expected: impl Into<String>) -> Self {
+        let offset = input.len() - rest.len();
+        let first = rest.chars().next().map_or(0, char::len_utf8);
+        let len = rest
+            .find(char::is_whitespace)
+            .unwrap_or(rest.len())
+            .max(first);
+        Self::at_offset(input, offset, len, expected)
+    }
+
+    /// The location of `found` in line `line` of `source`, where `column`
+    /// counts chars from 1.  For errors found while walking a line.
+    pub fn in_line(
+        line: usize,
+        source: &str,
+        column: usize,
+        found: impl Into<String>,
+        expected: impl Into<String>,
+    ) -> Self {
+        Self {
+            line,
+            column,
+            source: source.to_string(),
+            found: found.into(),
+            expected: expected.into(),
+        }
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let found = match self.found.is_empty() {
+            true => "end of line".to_string(),
+            false => format!("{:?}", self.found),
+        };
+        writeln!(
+            f,
+            "line {}, column {}: expected {}, found {}",
+            self.line, self.column, self.expected, found
+        )?;
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.source)?;
+        write!(
+            f,
+            "{} | {}{}",
+            gutter,
+            " ".repeat(self.column - 1),
+            "^".repeat(self.found.chars().count().max(1))
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_at_offset() {
+        let input = "123\n45x7\n";
+        let location = Location::at_offset(input, 6, 1, "a digit");
+        assert_eq!((location.line, location.column), (2, 3));
+        assert_eq!(location.source, "45x7");
+        assert_eq!(location.found, "x");
+        assert_eq!(
+            location.to_string(),
+            "line 2, column 3: expected a digit, found \"x\"\n  |\n2 | 45x7\n  |   ^"
+        );
+    }
+
+    #[test]
+    fn test_at_rest() {
+        let input = "a: 1\r\nb: two more";
+        let rest = &input[9..];
+        let location = Location::at_rest(input, rest, "a number");
+        assert_eq!((location.line, location.column), (2, 4));
+        assert_eq!(location.source, "b: two more");
+        assert_eq!(location.found, "two");
+        assert!(location.to_string().ends_with("|    ^^^"));
+    }
+}
@@ -1,27 +1,126 @@
 use anyhow::Result;
+use std::collections::HashMap;
 use std::fmt::Display;
 use std::ops::{Deref, DerefMut};
 use std::str::FromStr;
 use thiserror::Error;
 
-use crate::{Point, data_to_grid};
+use crate::{
+    Direction, Location, Point, Pose,
+    convert::{parse_grid, parse_grid_with},
+};
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Error)]
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
 pub enum MatrixError {
-    #[error("failed to parse")]
-    FailedToParse,
+    #[error("failed to parse at {0}")]
+    FailedToParse(Location),
+    #[error("the grid has no cells")]
+    Empty,
+    #[error("line {line} has {found} cells, expected {expected}")]
+    Ragged {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
     #[error("Point is out of range: {0}:{1} ")]
     OutOfRange(isize, isize),
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct Matrix<T: Copy + Display + TryFrom<char>> {
+pub struct Matrix<T: Copy> {
     pub grid: Vec<Vec<T>>,
     pub width: usize,
     pub height: usize,
 }
 
-impl<T: Copy + Display + TryFrom<char>> Matrix<T> {
+impl<T: Copy> Matrix<T> {
+    /// Parse `input` with `f` mapping each char to a cell.  Returns an
+    /// error at the first char that `f` maps to None.
+    ///
+    /// Example:
+    /// ```
+    /// # use aoc_utils::Matrix;
+    /// let matrix = Matrix::parse_with("#.\n.#", |c| Some(c == '#')).expect("failed to parse");
+    /// assert!(matrix[1][1]);
+    /// ```
+    pub fn parse_with<F>(input: &str, mut f: F) -> Result<Self, MatrixError>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let grid = parse_grid_with(input, "a valid cell", |_, _, c| f(c))
+            .map_err(MatrixError::FailedToParse)?;
+        Self::from_rows(grid)
+    }
+
+    /// Like `parse_with`, but each char in `markers` (such as `S` and `E`)
+    /// is replaced by `floor` instead of being passed to `f`.  Returns the
+    /// `Point`s where each marker was found, in reading order.
+    ///
+    /// Example:
+    /// ```
+    /// # use aoc_utils::{Matrix, Point};
+    /// let (matrix, markers) = Matrix::parse_with_markers("S.#\n#.E", &['S', 'E'], b'.', |c| {
+    ///     u8::try_from(c).ok()
+    /// })
+    /// .expect("failed to parse");
+    /// assert_eq!(markers[&'S'], vec![Point::new(0, 0)]);
+    /// assert_eq!(matrix.get_unsafe(&Point::new(2, 1)), b'.');
+    /// ```
+    pub fn parse_with_markers<F>(
+        input: &str,
+        markers: &[char],
+        floor: T,
+        mut f: F,
+    ) -> Result<(Self, HashMap<char, Vec<Point>>), MatrixError>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let mut found: HashMap<char, Vec<Point>> = HashMap::new();
+        let grid = parse_grid_with(input, "a valid cell", |x, y, c| {
+            match markers.contains(&c) {
+                true => {
+                    found.entry(c).or_default().push(Point::from((x, y)));
+                    Some(floor)
+                }
+                false => f(c),
+            }
+        })
+        .map_err(MatrixError::FailedToParse)?;
+        Ok((Self::from_rows(grid)?, found))
+    }
+
+    /// Build a matrix from its rows.  Returns an error if there are no
+    /// cells, or if the rows are not all the same length.  The width is
+    /// taken from the first row that isn't empty, so a blank first line is
+    /// reported as ragged.
+    pub fn from_rows(grid: Vec<Vec<T>>) -> Result<Self, MatrixError> {
+        let Some(width) = grid.iter().map(|row| row.len()).find(|&len| len > 0) else {
+            return Err(MatrixError::Empty);
+        };
+        if let Some((y, row)) = grid.iter().enumerate().find(|(_, row)| row.len() != width) {
+            return Err(MatrixError::Ragged {
+                line: y + 1,
+                expected: width,
+                found: row.len(),
+            });
+        }
+        Ok(Self {
+            height: grid.len(),
+            width,
+            grid,
+        })
+    }
+
+    /// Build a matrix from rows of any length, filling the end of each
+    /// short row with `fill`.  Returns an error if there are no cells.
+    pub fn from_rows_padded(mut grid: Vec<Vec<T>>, fill: T) -> Result<Self, MatrixError> {
+        let width = grid.iter().map(|row| row.len()).max().unwrap_or(0);
+        for row in grid.iter_mut() {
+            row.resize(width, fill);
+        }
+        Self::from_rows(grid)
+    }
+
     pub fn neighbors(&self, p: &Point) -> Vec<Point> {
         p.neighbors()
             .into_iter()
@@ -55,6 +154,20 @@ impl<T: Copy + Display + TryFrom<char>> Matrix<T> {
         }
     }
 
+    /// Gets the value one step from `point` in direction `dir`.
+    /// Returns Err if that position is not valid.
+    #[inline]
+    pub fn get_toward(&self, point: &Point, dir: Direction) -> Result<T> {
+        self.get(&(*point + dir))
+    }
+
+    /// Gets the value of the cell in front of `pose`.
+    /// Returns Err if that position is not valid.
+    #[inline]
+    pub fn get_ahead(&self, pose: &Pose) -> Result<T> {
+        self.get(&pose.ahead())
+    }
+
     /// Sets a value at position `Point`. Returns Err if the position is not valid.
     #[inline]
     pub fn set(&mut self, point: &Point, t: T) -> Result<()> {
@@ -78,30 +191,32 @@ impl<T: Copy + Display + TryFrom<char>> Matrix<T> {
     }
 }
 
-impl<T: TryFrom<char> + Copy + Display> TryFrom<&str> for Matrix<T> {
+impl<T: TryFrom<char> + Copy> TryFrom<&str> for Matrix<T> {
     type Error = MatrixError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let grid = data_to_grid(value).map_err(|_| MatrixError::FailedToParse)?;
+        let grid = parse_grid(value).map_err(MatrixError::FailedToParse)?;
+        Self::from_rows(grid)
+    }
+}
 
-        let height = grid.len();
-        let width = grid[0].len();
-        Ok(Self {
-            grid,
-            height,
-            width,
-        })
+impl<T: TryFrom<char> + Copy> Matrix<T> {
+    /// Parse `input`, filling the end of short lines with `fill`.  For
+    /// inputs where trailing spaces have been trimmed from some lines.
+    pub fn try_from_padded(input: &str, fill: T) -> Result<Self, MatrixError> {
+        let grid = parse_grid(input).map_err(MatrixError::FailedToParse)?;
+        Self::from_rows_padded(grid, fill)
     }
 }
 
-impl<T: TryFrom<char> + Copy + Display> FromStr for Matrix<T> {
+impl<T: TryFrom<char> + Copy> FromStr for Matrix<T> {
     type Err = MatrixError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Matrix::try_from(s)
     }
 }
 
-impl<T: TryFrom<char> + Copy + Display> Display for Matrix<T> {
+impl<T: Copy + Display> Display for Matrix<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for y in &self.grid {
             for x in y {
@@ -113,14 +228,14 @@ impl<T: TryFrom<char> + Copy + Display> Display for Matrix<T> {
     }
 }
 
-impl<T: TryFrom<char> + Copy + Display> Deref for Matrix<T> {
+impl<T: Copy> Deref for Matrix<T> {
     type Target = Vec<Vec<T>>;
     fn deref(&self) -> &Self::Target {
         &self.grid
     }
 }
 
-impl<T: TryFrom<char> + Copy + Display> DerefMut for Matrix<T> {
+impl<T: Copy> DerefMut for Matrix<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.grid
     }
@@ -156,6 +271,76 @@ mod tests {
         assert_eq!(matrix.unwrap(), result);
     }
 
+    #[test]
+    fn test_parse_error_location() {
+        let err = Matrix::<u8>::try_from("....\n..€.").unwrap_err();
+        let MatrixError::FailedToParse(location) = &err else {
+            panic!("unexpected error {:?}", err);
+        };
+        assert_eq!((location.line, location.column), (2, 3));
+        assert_eq!(location.found, "€");
+    }
+
+    #[test]
+    fn test_bad_shape() {
+        assert_eq!(Matrix::<u8>::try_from(""), Err(MatrixError::Empty));
+        assert_eq!(Matrix::<u8>::try_from("\n\n"), Err(MatrixError::Empty));
+        assert_eq!(
+            Matrix::<u8>::try_from("\n..."),
+            Err(MatrixError::Ragged {
+                line: 1,
+                expected: 3,
+                found: 0
+            })
+        );
+        assert_eq!(
+            Matrix::<u8>::try_from("...\n..\n..."),
+            Err(MatrixError::Ragged {
+                line: 2,
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_padded() {
+        let matrix = Matrix::try_from_padded("  #\n\n #..", b' ').expect("padded");
+        assert_eq!((matrix.width, matrix.height), (4, 3));
+        assert_eq!(matrix[1], vec![b' '; 4]);
+        assert_eq!(matrix.get_unsafe(&Point::new(3, 0)), b' ');
+        assert_eq!(
+            Matrix::from_rows_padded(vec![vec![]], 0u8),
+            Err(MatrixError::Empty)
+        );
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Tile {
+        Wall,
+        Open,
+    }
+
+    #[test]
+    fn test_parse_with() {
+        let tile = |c| match c {
+            '#' => Some(Tile::Wall),
+            '.' => Some(Tile::Open),
+            _ => None,
+        };
+        let matrix = Matrix::parse_with("#.\n..", tile).expect("failed to parse");
+        assert_eq!(matrix.get_unsafe(&Point::new(0, 0)), Tile::Wall);
+        let err = Matrix::parse_with("#.\n.@", tile).unwrap_err();
+        assert!(err.to_string().contains("line 2, column 2"));
+
+        let (matrix, markers) =
+            Matrix::parse_with_markers("#@.\nO.@", &['@', 'O'], Tile::Open, tile)
+                .expect("failed to parse");
+        assert_eq!(markers[&'@'], vec![Point::new(1, 0), Point::new(2, 1)]);
+        assert_eq!(markers[&'O'], vec![Point::new(0, 1)]);
+        assert!(matrix.iter().flatten().skip(1).all(|t| *t == Tile::Open));
+    }
+
     #[test]
     fn test_good_get() {
         let input = ".#..\n....\n....\n....\n....";
@@ -176,4 +361,16 @@ mod tests {
         let point = Point::from((matrix.width + 1, matrix.height + 1));
         assert!(matrix.get(&point).is_err());
     }
+
+    #[test]
+    fn test_get_ahead() {
+        let input = ".#..\n....\n....\n....\n....";
+        let matrix = input
+            .parse::<Matrix<u8>>()
+            .expect("Matrix failed to parse!");
+        let pose = Pose::new(Point::from((1, 1)), Direction::North);
+        assert_eq!(matrix.get_ahead(&pose).unwrap(), b'#');
+        assert_eq!(matrix.get_toward(&pose.pos, Direction::East).unwrap(), b'.');
+        assert!(matrix.get_ahead(&pose.forward()).is_err());
+    }
 }
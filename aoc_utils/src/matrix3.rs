@@ -0,0 +1,224 @@
+use anyhow::Result;
+use std::collections::{HashSet, VecDeque};
+use thiserror::Error;
+
+use crate::Point3;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Error)]
+pub enum Matrix3Error {
+    #[error("Point is out of range: {0}:{1}:{2}")]
+    OutOfRange(isize, isize, isize),
+}
+
+/// A dense 3D grid, indexed by `Point3`.
+/// Cells are stored flat, x first, then y, then z.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix3<T: Copy> {
+    pub cells: Vec<T>,
+    pub width: usize,
+    pub height: usize,
+    pub depth: usize,
+}
+
+impl<T: Copy> Matrix3<T> {
+    /// Create a grid of `width` x `height` x `depth`, with every cell set to `fill`
+    pub fn new(width: usize, height: usize, depth: usize, fill: T) -> Self {
+        Self {
+            cells: vec![fill; width * height * depth],
+            width,
+            height,
+            depth,
+        }
+    }
+
+    /// Create the smallest grid starting at the origin that holds every point.
+    /// Each point is set to `value`, and every other cell to `fill`.
+    /// Points with negative coordinates are ignored.
+    pub fn from_points<'a>(
+        points: impl IntoIterator<Item = &'a Point3> + Clone,
+        value: T,
+        fill: T,
+    ) -> Self {
+        let max = points
+            .clone()
+            .into_iter()
+            .fold(Point3::default(), |acc, p| Point3 {
+                x: acc.x.max(p.x),
+                y: acc.y.max(p.y),
+                z: acc.z.max(p.z),
+            });
+        let mut matrix = Self::new(
+            max.x as usize + 1,
+            max.y as usize + 1,
+            max.z as usize + 1,
+            fill,
+        );
+        for p in points {
+            if matrix.valid_point(p) {
+                matrix.set_unsafe(p, value);
+            }
+        }
+        matrix
+    }
+
+    #[inline]
+    pub fn valid_point(&self, point: &Point3) -> bool {
+        point.x >= 0
+            && point.x < self.width as isize
+            && point.y >= 0
+            && point.y < self.height as isize
+            && point.z >= 0
+            && point.z < self.depth as isize
+    }
+
+    #[inline]
+    fn index(&self, point: &Point3) -> usize {
+        (point.z as usize * self.height + point.y as usize) * self.width + point.x as usize
+    }
+
+    /// The face-adjacent neighbors of `p` that are inside the grid
+    pub fn neighbors_6(&self, p: &Point3) -> Vec<Point3> {
+        p.neighbors_6().filter(|q| self.valid_point(q)).collect()
+    }
+
+    /// Gets a value at position `Point3`. Returns Err if the position is not valid.
+    #[inline]
+    pub fn get(&self, point: &Point3) -> Result<T> {
+        match self.valid_point(point) {
+            true => Ok(self.cells[self.index(point)]),
+            false => Err(Matrix3Error::OutOfRange(point.x, point.y, point.z).into()),
+        }
+    }
+
+    /// Gets a value at position `Point3`. Panics if the position is not valid.
+    #[inline]
+    pub fn get_unsafe(&self, point: &Point3) -> T {
+        match self.valid_point(point) {
+            true => self.cells[self.index(point)],
+            false => panic!("Out of range: [{}:{}:{}]", point.x, point.y, point.z),
+        }
+    }
+
+    /// Sets a value at position `Point3`. Returns Err if the position is not valid.
+    #[inline]
+    pub fn set(&mut self, point: &Point3, t: T) -> Result<()> {
+        match self.valid_point(point) {
+            true => {
+                let index = self.index(point);
+                self.cells[index] = t;
+                Ok(())
+            }
+            false => Err(Matrix3Error::OutOfRange(point.x, point.y, point.z).into()),
+        }
+    }
+
+    /// Sets a value at position `Point3`. Panics if the position is not valid.
+    #[inline]
+    pub fn set_unsafe(&mut self, point: &Point3, t: T) {
+        match self.valid_point(point) {
+            true => {
+                let index = self.index(point);
+                self.cells[index] = t;
+            }
+            false => panic!("Out of range: [{}:{}:{}]", point.x, point.y, point.z),
+        }
+    }
+
+    /// Iterate over every position in the grid
+    pub fn points(&self) -> impl Iterator<Item = Point3> + use<T> {
+        let (width, height, depth) = (self.width, self.height, self.depth);
+        (0..depth).flat_map(move |z| {
+            (0..height).flat_map(move |y| (0..width).map(move |x| Point3::from((x, y, z))))
+        })
+    }
+
+    /// Number of faces of solid cells that touch a non solid cell, or the
+    /// outside of the grid.  Includes the faces of interior pockets.
+    pub fn surface_area<F: Fn(T) -> bool>(&self, is_solid: F) -> usize {
+        self.points()
+            .filter(|p| is_solid(self.get_unsafe(p)))
+            .map(|p| {
+                p.neighbors_6()
+                    .filter(|q| !self.valid_point(q) || !is_solid(self.get_unsafe(q)))
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Flood fill the empty space around the solid cells, starting outside
+    /// the grid.  Returns every reachable point, including the one cell
+    /// border around the grid.  Enclosed pockets are not included.
+    pub fn exterior<F: Fn(T) -> bool>(&self, is_solid: F) -> HashSet<Point3> {
+        let in_border = |p: &Point3| {
+            p.x >= -1
+                && p.x <= self.width as isize
+                && p.y >= -1
+                && p.y <= self.height as isize
+                && p.z >= -1
+                && p.z <= self.depth as isize
+        };
+        let start = Point3::new(-1, -1, -1);
+        let mut seen = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(p) = queue.pop_front() {
+            for q in p.neighbors_6() {
+                if !in_border(&q) || seen.contains(&q) {
+                    continue;
+                }
+                if self.valid_point(&q) && is_solid(self.get_unsafe(&q)) {
+                    continue;
+                }
+                seen.insert(q);
+                queue.push_back(q);
+            }
+        }
+        seen
+    }
+
+    /// Number of faces of solid cells that can be reached from outside.
+    /// Faces of enclosed pockets are not counted.
+    pub fn exterior_surface_area<F: Fn(T) -> bool>(&self, is_solid: F) -> usize {
+        let exterior = self.exterior(&is_solid);
+        self.points()
+            .filter(|p| is_solid(self.get_unsafe(p)))
+            .map(|p| p.neighbors_6().filter(|q| exterior.contains(q)).count())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 3x3x3 cube, with a hollow center
+    fn hollow_cube() -> Matrix3<bool> {
+        let mut matrix = Matrix3::new(3, 3, 3, true);
+        matrix.set_unsafe(&Point3::new(1, 1, 1), false);
+        matrix
+    }
+
+    #[test]
+    fn test_get_set() {
+        let mut matrix = Matrix3::new(2, 3, 4, 0u8);
+        let p = Point3::new(1, 2, 3);
+        assert!(matrix.set(&p, 7).is_ok());
+        assert_eq!(matrix.get(&p).unwrap(), 7);
+        assert!(matrix.get(&Point3::new(2, 0, 0)).is_err());
+    }
+
+    #[test]
+    fn test_surface_area() {
+        let matrix = hollow_cube();
+        assert_eq!(matrix.surface_area(|c| c), 54 + 6);
+        assert_eq!(matrix.exterior_surface_area(|c| c), 54);
+    }
+
+    #[test]
+    fn test_from_points() {
+        let points = [Point3::new(1, 1, 1), Point3::new(2, 1, 1)];
+        let matrix = Matrix3::from_points(&points, true, false);
+        assert_eq!((matrix.width, matrix.height, matrix.depth), (3, 2, 2));
+        assert_eq!(matrix.surface_area(|c| c), 10);
+        assert_eq!(matrix.exterior_surface_area(|c| c), 10);
+    }
+}
@@ -0,0 +1,149 @@
+//! Modular arithmetic helpers, for puzzles where things line up on a cycle.
+//!
+//! The math is done in `i128` (or `u128` for `mod_pow`), and results are
+//! checked on the way back to `T`.  Functions return None rather than
+//! overflowing.
+use num::traits::NumCast;
+use num::{Integer, PrimInt};
+
+/// Returns `(g, x, y)` where `g = gcd(a, b)` and `a * x + b * y = g`
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// Returns `x` in `0..m` such that `a * x ≡ 1 (mod m)`.
+/// Returns None if `a` and `m` are not coprime, or `m` is not positive.
+pub fn mod_inverse<T: PrimInt>(a: T, m: T) -> Option<T> {
+    let (a, m): (i128, i128) = (NumCast::from(a)?, NumCast::from(m)?);
+    if m <= 0 {
+        return None;
+    }
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    match g == 1 {
+        true => NumCast::from(x.rem_euclid(m)),
+        false => None,
+    }
+}
+
+/// Returns `base^exp mod m`, using `u128` so the products can't overflow.
+/// Panics if `m` is zero.
+pub fn mod_pow(base: u64, mut exp: u64, m: u64) -> u64 {
+    assert!(m != 0, "mod_pow with a modulus of zero");
+    let m = m as u128;
+    let mut base = base as u128 % m;
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    result as u64
+}
+
+/// Least common multiple of every value, which is never negative.
+/// Returns None if the result overflows `T`, and `T::one()` for an empty
+/// iterator.
+pub fn lcm_all<T: PrimInt + Integer>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    values.into_iter().try_fold(T::one(), |acc, v| {
+        let v = match v < T::zero() {
+            true => T::zero().checked_sub(&v)?,
+            false => v,
+        };
+        if acc.is_zero() || v.is_zero() {
+            return Some(T::zero());
+        }
+        (acc / acc.gcd(&v)).checked_mul(&v)
+    })
+}
+
+/// Chinese remainder theorem: find `x` such that `x ≡ r (mod m)` for every
+/// `(r, m)` pair.  The moduli don't need to be coprime.
+///
+/// Returns `(x, lcm)` where `x` is the smallest non negative solution and
+/// every `x + k * lcm` is also a solution.  Returns None if the congruences
+/// conflict, a modulus is not positive, or the result overflows `T`.
+pub fn crt<T: PrimInt>(congruences: impl IntoIterator<Item = (T, T)>) -> Option<(T, T)> {
+    let (mut x, mut lcm): (i128, i128) = (0, 1);
+    for (r, m) in congruences {
+        let (r, m): (i128, i128) = (NumCast::from(r)?, NumCast::from(m)?);
+        if m <= 0 {
+            return None;
+        }
+        let r = r.rem_euclid(m);
+        let (g, p, _) = extended_gcd(lcm, m);
+        if (r - x) % g != 0 {
+            return None;
+        }
+        // Solve lcm * k ≡ r - x (mod m)
+        let step = m / g;
+        let k = ((r - x) / g)
+            .rem_euclid(step)
+            .checked_mul(p.rem_euclid(step))?
+            % step;
+        x = lcm.checked_mul(k)?.checked_add(x)?;
+        lcm = lcm.checked_mul(step)?;
+        x = x.rem_euclid(lcm);
+    }
+    Some((NumCast::from(x)?, NumCast::from(lcm)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extended_gcd() {
+        let (g, x, y) = extended_gcd(240, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3i64, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(mod_pow(4, 13, 497), 445);
+        assert_eq!(mod_pow(u64::MAX, u64::MAX, u64::MAX - 1), 1);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+    }
+
+    #[test]
+    fn test_lcm_all() {
+        assert_eq!(lcm_all([2u64, 3, 4, 5]), Some(60));
+        assert_eq!(lcm_all(Vec::<u32>::new()), Some(1));
+        assert_eq!(lcm_all([u64::MAX, u64::MAX - 1]), None);
+        assert_eq!(lcm_all([-4i64, 6]), Some(12));
+        assert_eq!(lcm_all([-4i64, -6]), Some(12));
+        assert_eq!(lcm_all([i8::MIN]), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        // The bus schedule example from 2020 day 13: 7,13,x,x,59,x,31,19
+        let buses: [(i64, i64); 5] = [(0, 7), (-1, 13), (-4, 59), (-6, 31), (-7, 19)];
+        assert_eq!(crt(buses), Some((1068781, 3162341)));
+        // Moduli that share factors
+        assert_eq!(crt([(2u64, 6), (4, 8)]), Some((20, 24)));
+        assert_eq!(crt([(1u64, 6), (2, 8)]), None);
+    }
+}
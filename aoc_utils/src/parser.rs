@@ -2,54 +2,276 @@
 use std::str::FromStr;
 
 use nom::{
-    IResult,
-    character::complete::{digit1, one_of},
-    combinator::{map_res, opt, recognize},
-    sequence::tuple,
+    Err, IResult, Parser,
+    branch::alt,
+    bytes::complete::{tag, take_till1, take_while1},
+    character::complete::{char, digit1, line_ending, one_of, space0, space1},
+    combinator::{map, map_res, opt, recognize, verify},
+    error::{Error, ErrorKind},
+    multi::{many1, separated_list0, separated_list1},
+    sequence::{delimited, separated_pair},
 };
+use thiserror::Error;
+
+use crate::Location;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParserError {
+    #[error("failed to parse at {0}")]
+    Failed(Location),
+    #[error("input ended early")]
+    Incomplete,
+}
+
+/// Run `parser` over the whole of `input`.  Any error, or any input left
+/// over, gives the line and column where parsing stopped.
+/// Example:
+/// ```
+/// # use aoc_utils::parser::*;
+/// let err = parse_all("Time: x", key_value(ws_integers::<u32>)).unwrap_err();
+/// assert!(err.to_string().contains("line 1, column 7: expected a digit"));
+/// ```
+pub fn parse_all<'a, O, F>(input: &'a str, mut parser: F) -> Result<O, ParserError>
+where
+    F: Parser<&'a str, Output = O, Error = Error<&'a str>>,
+{
+    match parser.parse(input) {
+        Ok(("", output)) => Ok(output),
+        Ok((rest, _)) => Err(ParserError::Failed(Location::at_rest(
+            input,
+            rest,
+            "end of input",
+        ))),
+        Err(Err::Error(e) | Err::Failure(e)) => Err(ParserError::Failed(Location::at_rest(
+            input,
+            e.input,
+            expected(e.code),
+        ))),
+        Err(Err::Incomplete(_)) => Err(ParserError::Incomplete),
+    }
+}
+
+/// What nom was looking for, in words
+fn expected(kind: ErrorKind) -> String {
+    match kind {
+        ErrorKind::Digit => "a digit".to_string(),
+        ErrorKind::MapRes => "a number that fits".to_string(),
+        ErrorKind::OneOf => "a sign or digit".to_string(),
+        ErrorKind::Char => "a separator".to_string(),
+        ErrorKind::Tag => "a keyword".to_string(),
+        ErrorKind::TakeWhile1 => "a name".to_string(),
+        ErrorKind::CrLf => "a line ending".to_string(),
+        ErrorKind::Eof => "end of input".to_string(),
+        kind => kind.description().to_lowercase(),
+    }
+}
 
 /// Parse a simple number, such as "123".
 /// Returns a str.
 /// Example:
 /// ```
-/// let (_, result) = parsed_number::<usize>("123").expect("failed to parse");
+/// # use aoc_utils::parser::*;
+/// let (_, result) = number("123").expect("failed to parse");
 /// assert_eq!(result, "123");
 /// ```
 pub fn number(input: &str) -> IResult<&str, &str> {
-    recognize(digit1)(input)
+    recognize(digit1).parse(input)
 }
 
 /// Parse a simple number, such as "123", to type T.
 /// Returns T
 /// Example:
 /// ```
+/// # use aoc_utils::parser::*;
 /// let (_, result) = parsed_number::<usize>("123").expect("failed to parse");
 /// assert_eq!(result, 123usize);
 /// ```
 pub fn parsed_number<T: FromStr>(input: &str) -> IResult<&str, T> {
-    map_res(number, str::parse)(input)
+    map_res(number, str::parse).parse(input)
 }
 
 /// Parse a signed integer.
 /// Returns a str.
 /// Example:
 /// ```
-/// let (_, result) = integer::<usize>("-123").expect("failed to parse");
+/// # use aoc_utils::parser::*;
+/// let (_, result) = integer("-123").expect("failed to parse");
 /// assert_eq!(result, "-123");
 /// ```
 pub fn integer(input: &str) -> IResult<&str, &str> {
-    recognize(tuple((opt(one_of("+-")), number)))(input)
+    recognize((opt(one_of("+-")), number)).parse(input)
 }
 
 /// Parse a signed integer, such as "-123", to type T.
 /// Returns T
 /// Example:
 /// ```
+/// # use aoc_utils::parser::*;
 /// let (_, result) = parsed_integer::<isize>("-123").expect("failed to parse");
 /// assert_eq!(result, -123isize);
 /// ```
 pub fn parsed_integer<T: FromStr>(input: &str) -> IResult<&str, T> {
-    map_res(integer, str::parse)(input)
+    map_res(integer, str::parse).parse(input)
+}
+
+/// Extract every signed integer from `input`, skipping anything else.
+/// A `-` right before a digit is always a sign, so "1-2" gives `[1, -2]`.
+/// Integers that don't fit in T are skipped.
+/// Example:
+/// ```
+/// # use aoc_utils::parser::*;
+/// let result = all_integers::<i64>("p=0,4 v=3,-3");
+/// assert_eq!(result, vec![0, 4, 3, -3]);
+/// ```
+pub fn all_integers<T: FromStr>(input: &str) -> Vec<T> {
+    let mut result = Vec::new();
+    let mut rest = input;
+    while let Some(c) = rest.chars().next() {
+        rest = match integer(rest) {
+            Ok((after, text)) => {
+                result.extend(text.parse().ok());
+                after
+            }
+            Err(_) => &rest[c.len_utf8()..],
+        };
+    }
+    result
+}
+
+/// Parse a name made of letters, digits and `_`, such as "node_1".
+/// Example:
+/// ```
+/// # use aoc_utils::parser::*;
+/// let (rest, result) = name("abc_1: 2").expect("failed to parse");
+/// assert_eq!((rest, result), (": 2", "abc_1"));
+/// ```
+pub fn name(input: &str) -> IResult<&str, &str> {
+    take_while1(|c: char| c.is_alphanumeric() || c == '_').parse(input)
+}
+
+/// A comma, with optional spaces on either side
+fn comma(input: &str) -> IResult<&str, char> {
+    delimited(space0, char(','), space0).parse(input)
+}
+
+/// Parse integers separated by spaces or tabs, such as "1  2 -3".
+/// Example:
+/// ```
+/// # use aoc_utils::parser::*;
+/// let (_, result) = ws_integers::<i32>("1  2 -3").expect("failed to parse");
+/// assert_eq!(result, vec![1, 2, -3]);
+/// ```
+pub fn ws_integers<T: FromStr>(input: &str) -> IResult<&str, Vec<T>> {
+    separated_list1(space1, parsed_integer).parse(input)
+}
+
+/// Parse integers separated by commas, such as "1,2, -3".
+/// Example:
+/// ```
+/// # use aoc_utils::parser::*;
+/// let (_, result) = comma_integers::<i32>("1,2, -3").expect("failed to parse");
+/// assert_eq!(result, vec![1, 2, -3]);
+/// ```
+pub fn comma_integers<T: FromStr>(input: &str) -> IResult<&str, Vec<T>> {
+    separated_list1(comma, parsed_integer).parse(input)
+}
+
+/// Parse integers separated by commas or spaces, such as "1, 2 3".
+/// Example:
+/// ```
+/// # use aoc_utils::parser::*;
+/// let (_, result) = integer_list::<u8>("1, 2 3").expect("failed to parse");
+/// assert_eq!(result, vec![1, 2, 3]);
+/// ```
+pub fn integer_list<T: FromStr>(input: &str) -> IResult<&str, Vec<T>> {
+    separated_list1(alt((recognize(comma), space1)), parsed_integer).parse(input)
+}
+
+/// Parse a `key: value` line, using `value` to parse the part after the colon.
+/// The key is everything before the colon, trimmed, so it may hold spaces.
+/// Example:
+/// ```
+/// # use aoc_utils::parser::*;
+/// # use nom::Parser;
+/// let (_, result) = key_value(ws_integers::<u32>)
+///     .parse("Time: 7 15 30")
+///     .expect("failed to parse");
+/// assert_eq!(result, ("Time", vec![7, 15, 30]));
+/// ```
+pub fn key_value<'a, O, F>(
+    value: F,
+) -> impl Parser<&'a str, Output = (&'a str, O), Error = Error<&'a str>>
+where
+    F: Parser<&'a str, Output = O, Error = Error<&'a str>>,
+{
+    let key = verify(
+        map(take_till1(|c| c == ':' || c == '\n'), str::trim),
+        |key: &str| !key.is_empty(),
+    );
+    separated_pair(key, (char(':'), space0), value)
+}
+
+/// Parse a `name -> a, b, c` adjacency line.
+/// Example:
+/// ```
+/// # use aoc_utils::parser::*;
+/// let (_, result) = adjacency("broadcaster -> a, b, c").expect("failed to parse");
+/// assert_eq!(result, ("broadcaster", vec!["a", "b", "c"]));
+/// ```
+pub fn adjacency(input: &str) -> IResult<&str, (&str, Vec<&str>)> {
+    separated_pair(
+        name,
+        delimited(space0, tag("->"), space0),
+        separated_list1(comma, name),
+    )
+    .parse(input)
+}
+
+/// Parse a comma separated list between `open` and `close`, such as "[1,2,3]".
+/// The list may be empty.
+/// Example:
+/// ```
+/// # use aoc_utils::parser::*;
+/// # use nom::Parser;
+/// let (_, result) = bracketed('[', ']', parsed_integer::<i32>)
+///     .parse("[1, -2, 3]")
+///     .expect("failed to parse");
+/// assert_eq!(result, vec![1, -2, 3]);
+/// ```
+pub fn bracketed<'a, O, F>(
+    open: char,
+    close: char,
+    item: F,
+) -> impl Parser<&'a str, Output = Vec<O>, Error = Error<&'a str>>
+where
+    F: Parser<&'a str, Output = O, Error = Error<&'a str>>,
+{
+    delimited(
+        (char(open), space0),
+        separated_list0(comma, item),
+        (space0, char(close)),
+    )
+}
+
+/// Parse sections separated by one or more blank lines, using `section` for
+/// each one.  `section` must stop before the blank line.
+/// Example:
+/// ```
+/// # use aoc_utils::parser::*;
+/// # use nom::{Parser, character::complete::line_ending, multi::separated_list1};
+/// let input = "1\n2\n\n3";
+/// let (_, result) = sections(separated_list1(line_ending, parsed_number::<u32>))
+///     .parse(input)
+///     .expect("failed to parse");
+/// assert_eq!(result, vec![vec![1, 2], vec![3]]);
+/// ```
+pub fn sections<'a, O, F>(
+    section: F,
+) -> impl Parser<&'a str, Output = Vec<O>, Error = Error<&'a str>>
+where
+    F: Parser<&'a str, Output = O, Error = Error<&'a str>>,
+{
+    separated_list1((line_ending, many1(line_ending)), section)
 }
 
 #[cfg(test)]
@@ -79,4 +301,74 @@ mod tests {
         let (_, result) = parsed_integer::<i64>("-10").expect("failed");
         assert_eq!(result, -10);
     }
+
+    #[test]
+    fn test_all_integers() {
+        let result = all_integers::<i32>("Button A: X+94, Y-34 -- 1-2");
+        assert_eq!(result, vec![94, -34, 1, -2]);
+        assert_eq!(all_integers::<u8>("1 300 -4 5"), vec![1, 5]);
+        assert!(all_integers::<u8>("none").is_empty());
+    }
+
+    #[test]
+    fn test_parse_all() {
+        assert_eq!(parse_all("1,2", comma_integers::<u8>), Ok(vec![1, 2]));
+        let Err(ParserError::Failed(location)) = parse_all("1,2;3", comma_integers::<u8>) else {
+            panic!("expected a failure");
+        };
+        assert_eq!((location.column, location.found.as_str()), (4, ";3"));
+        assert_eq!(location.expected, "end of input");
+        let Err(ParserError::Failed(location)) = parse_all("ab ->", adjacency) else {
+            panic!("expected a failure");
+        };
+        assert_eq!((location.column, location.expected.as_str()), (6, "a name"));
+    }
+
+    #[test]
+    fn test_integer_lists() {
+        let (rest, result) = ws_integers::<i64>("3   4 -5\n6").expect("failed");
+        assert_eq!((rest, result), ("\n6", vec![3, 4, -5]));
+        let (rest, result) = comma_integers::<u32>("75,47 , 61,53|29").expect("failed");
+        assert_eq!((rest, result), ("|29", vec![75, 47, 61, 53]));
+        let (_, result) = integer_list::<u32>("1,2 3 , 4").expect("failed");
+        assert_eq!(result, vec![1, 2, 3, 4]);
+        let (rest, result) = integer_list::<u32>("1  2\t\t3 \t4").expect("failed");
+        assert_eq!((rest, result), ("", vec![1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn test_key_value() {
+        let (_, result) = key_value(parsed_number::<u64>)
+            .parse("Register A: 729")
+            .expect("failed");
+        assert_eq!(result, ("Register A", 729));
+        let (_, result) = key_value(name).parse("Button A: X+94").expect("failed");
+        assert_eq!(result, ("Button A", "X"));
+        assert!(key_value(name).parse(" : x").is_err());
+        assert!(key_value(name).parse("a\nb: x").is_err());
+    }
+
+    #[test]
+    fn test_adjacency() {
+        let (_, result) = adjacency("jqt -> rhn,xhk, nvd").expect("failed");
+        assert_eq!(result, ("jqt", vec!["rhn", "xhk", "nvd"]));
+    }
+
+    #[test]
+    fn test_bracketed() {
+        let (_, result) = bracketed('(', ')', parsed_number::<u8>)
+            .parse("()")
+            .expect("failed");
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_sections() {
+        let input = "1,2\n3,4\n\n\n5,6";
+        let (rest, result) = sections(separated_list1(line_ending, comma_integers::<u32>))
+            .parse(input)
+            .expect("failed");
+        assert_eq!(rest, "");
+        assert_eq!(result, vec![vec![vec![1, 2], vec![3, 4]], vec![vec![5, 6]]]);
+    }
 }
@@ -1,53 +1,83 @@
+use num::iter::range_inclusive;
+use num::traits::{NumAssign, NumCast};
+use num::{PrimInt, Signed, Zero};
 use std::fmt::Display;
 use std::ops::{Add, AddAssign, Rem, Sub, SubAssign};
+use std::str::FromStr;
 
-static NEIGHBORS_HV: [Point; 4] = [
-    Point { y: -1, x: 0 },
-    Point { y: 0, x: -1 },
-    Point { y: 0, x: 1 },
-    Point { y: 1, x: 0 },
-];
-
-static NEIGHBORS: [Point; 8] = [
-    Point { y: -1, x: -1 },
-    Point { y: -1, x: 0 },
-    Point { y: -1, x: 1 },
-    Point { y: 0, x: -1 },
-    Point { y: 0, x: 1 },
-    Point { y: 1, x: -1 },
-    Point { y: 1, x: 0 },
-    Point { y: 1, x: 1 },
-];
+use crate::{ScanError, parse_field, scan_fields};
 
+/// Numeric types that can be used as `Point` coordinates.
+/// Implemented for all of the primitive integer types, and nothing else.
+pub trait Coord: PrimInt + NumAssign {}
+
+impl<T: PrimInt + NumAssign> Coord for T {}
+
+/// How distance is measured by `Point::within`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Metric {
+    /// Sum of the horizontal and vertical distance
+    Manhattan,
+    /// Largest of the horizontal and vertical distance
+    Chebyshev,
+    /// Straight line distance
+    Euclidean,
+}
+
+/// A 2D point.  Coordinates are `isize` unless another `Coord` type is given,
+/// such as `Point<i64>` for large coordinates, or `Point<u16>` for small grids.
 #[derive(Debug, PartialEq, Clone, Copy, Hash, Eq, Default)]
-pub struct Point {
-    pub x: isize,
-    pub y: isize,
+pub struct Point<T = isize> {
+    pub x: T,
+    pub y: T,
 }
 
-impl Point {
+impl<T: Coord> Point<T> {
+    pub fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+
     pub fn mul(&self, factor: usize) -> Self {
+        let factor: T = NumCast::from(factor).expect("factor does not fit the coordinate type");
         Self {
-            y: self.y * factor as isize,
-            x: self.x * factor as isize,
+            y: self.y * factor,
+            x: self.x * factor,
         }
     }
 
+    /// Convert to another coordinate type that can hold every value of `T`,
+    /// such as `Point<i32>` to `Point<i64>`
+    pub fn cast<U: From<T>>(self) -> Point<U> {
+        Point {
+            x: self.x.into(),
+            y: self.y.into(),
+        }
+    }
+
+    /// Convert to another coordinate type.
+    /// Returns Err if either coordinate does not fit.
+    pub fn try_cast<U: TryFrom<T>>(self) -> Result<Point<U>, U::Error> {
+        Ok(Point {
+            x: self.x.try_into()?,
+            y: self.y.try_into()?,
+        })
+    }
+
     #[inline]
     /// Absolute distance between rows
-    pub fn vertical_distance(&self, other: &Self) -> isize {
-        self.y.max(other.y) - self.y.min(other.y)
+    pub fn vertical_distance(&self, other: &Self) -> T {
+        max(self.y, other.y) - min(self.y, other.y)
     }
 
     #[inline]
     /// Absolute distance between cols
-    pub fn horizontal_distance(&self, other: &Self) -> isize {
-        self.x.max(other.x) - self.x.min(other.x)
+    pub fn horizontal_distance(&self, other: &Self) -> T {
+        max(self.x, other.x) - min(self.x, other.x)
     }
 
     #[inline]
     /// Moves a point on a grid, and wraps, if needed
-    pub fn add_with_wrap(&self, rhs: &Point, max: &Self) -> Self {
+    pub fn add_with_wrap(&self, rhs: &Self, max: &Self) -> Self {
         Self {
             x: (self.x + rhs.x + max.x) % max.x,
             y: (self.y + rhs.y + max.y) % max.y,
@@ -62,16 +92,66 @@ impl Point {
         }
     }
 
+    /// Sum of the horizontal and vertical distance between two points
+    #[inline]
+    pub fn manhattan(&self, other: &Self) -> T {
+        self.horizontal_distance(other) + self.vertical_distance(other)
+    }
+
+    /// Largest of the horizontal and vertical distance between two points
+    #[inline]
+    pub fn chebyshev(&self, other: &Self) -> T {
+        max(
+            self.horizontal_distance(other),
+            self.vertical_distance(other),
+        )
+    }
+
+    /// Square of the straight line distance between two points
+    #[inline]
+    pub fn euclidean_squared(&self, other: &Self) -> T {
+        let (dx, dy) = (
+            self.horizontal_distance(other),
+            self.vertical_distance(other),
+        );
+        dx * dx + dy * dy
+    }
+
+    /// Straight line distance between two points
+    #[inline]
+    pub fn euclidean(&self, other: &Self) -> f64 {
+        self.euclidean_squared(other)
+            .to_f64()
+            .expect("distance does not fit in f64")
+            .sqrt()
+    }
+
+    /// True if `other` is no more than `radius` away, as measured by `metric`.
+    /// Always false for a negative radius.
+    pub fn within(&self, other: &Self, radius: T, metric: Metric) -> bool {
+        if radius < T::zero() {
+            return false;
+        }
+        match metric {
+            Metric::Manhattan => self.manhattan(other) <= radius,
+            Metric::Chebyshev => self.chebyshev(other) <= radius,
+            Metric::Euclidean => self.euclidean_squared(other) <= radius * radius,
+        }
+    }
+
     /// True if the points touch horizontally or vertically.
     /// if `check_diag` then checks to see if they touch on the corners
-    pub fn touches(&self, other: &Point, check_diag: bool) -> bool {
+    pub fn touches(&self, other: &Self, check_diag: bool) -> bool {
         let distance = self.distance(other);
         if self == other {
             return false;
         }
+        let (zero, one) = (T::zero(), T::one());
         match check_diag {
-            true => distance.y == 1 && distance.x == 1,
-            false => distance.y == 0 && distance.x == 1 || distance.y == 1 && distance.x == 0,
+            true => distance.y == one && distance.x == one,
+            false => {
+                distance.y == zero && distance.x == one || distance.y == one && distance.x == zero
+            }
         }
     }
 
@@ -83,13 +163,14 @@ impl Point {
                 false => acc,
             })
     }
+}
 
-    pub fn neighbors_hv(&self) -> Vec<Point> {
-        NEIGHBORS_HV.iter().map(|dir| *self + *dir).collect()
-    }
-
-    pub fn neighbors(&self) -> Vec<Point> {
-        NEIGHBORS.iter().map(|dir| *self + *dir).collect()
+impl<T: Coord + Signed> Point<T> {
+    pub(crate) fn offset(dx: isize, dy: isize) -> Self {
+        Self {
+            x: NumCast::from(dx).expect("offset does not fit the coordinate type"),
+            y: NumCast::from(dy).expect("offset does not fit the coordinate type"),
+        }
     }
 
     pub fn rem_euclid(&mut self, rhs: Self) -> Self {
@@ -98,31 +179,122 @@ impl Point {
             y: (self.y % rhs.y).abs(),
         }
     }
+}
+
+/// Neighbors and compass steps work for unsigned coordinates too.
+/// Points that would fall outside the coordinate type, such as west of
+/// `x == 0` for a `Point<u16>`, are skipped by `neighbors`, `neighbors_hv`
+/// and `manhattan_area`, and make the compass methods panic.
+impl<T: Coord> Point<T> {
+    /// This point moved by `dx` and `dy`, or None if the result does not
+    /// fit the coordinate type
+    pub fn checked_offset(&self, dx: isize, dy: isize) -> Option<Self> {
+        Some(Self {
+            x: shift(self.x, dx)?,
+            y: shift(self.y, dy)?,
+        })
+    }
 
-    pub fn north(&self) -> Point {
-        *self + Self { y: -1, x: 0 }
+    fn compass(&self, dx: isize, dy: isize) -> Self {
+        self.checked_offset(dx, dy)
+            .unwrap_or_else(|| panic!("step ({},{}) leaves the coordinate type", dx, dy))
     }
-    pub fn east(&self) -> Point {
-        *self + Self { y: 0, x: 1 }
+
+    pub fn neighbors_hv(&self) -> Vec<Self> {
+        [(0, -1), (-1, 0), (1, 0), (0, 1)]
+            .into_iter()
+            .filter_map(|(dx, dy)| self.checked_offset(dx, dy))
+            .collect()
+    }
+
+    pub fn neighbors(&self) -> Vec<Self> {
+        [
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+            (-1, 0),
+            (1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+        ]
+        .into_iter()
+        .filter_map(|(dx, dy)| self.checked_offset(dx, dy))
+        .collect()
+    }
+
+    /// Every point no more than `radius` away by Manhattan distance,
+    /// including this one.  Rows are visited top to bottom.
+    pub fn manhattan_area(&self, radius: T) -> impl Iterator<Item = Self> + use<T> {
+        let center = *self;
+        range_inclusive(T::zero(), radius + radius).flat_map(move |i| {
+            let y = towards(center.y, i, radius);
+            let span = match i < radius {
+                true => i,
+                false => radius + radius - i,
+            };
+            range_inclusive(T::zero(), span + span).filter_map(move |j| {
+                Some(Self {
+                    x: towards(center.x, j, span)?,
+                    y: y?,
+                })
+            })
+        })
+    }
+
+    pub fn north(&self) -> Self {
+        self.compass(0, -1)
     }
-    pub fn south(&self) -> Point {
-        *self + Self { y: 1, x: 0 }
+    pub fn east(&self) -> Self {
+        self.compass(1, 0)
     }
-    pub fn west(&self) -> Point {
-        *self + Self { y: 0, x: -1 }
+    pub fn south(&self) -> Self {
+        self.compass(0, 1)
     }
-    pub fn north_west(&self) -> Point {
-        *self + Self { y: -1, x: -1 }
+    pub fn west(&self) -> Self {
+        self.compass(-1, 0)
     }
-    pub fn north_east(&self) -> Point {
-        *self + Self { y: -1, x: 1 }
+    pub fn north_west(&self) -> Self {
+        self.compass(-1, -1)
     }
-    pub fn south_east(&self) -> Point {
-        *self + Self { y: 1, x: 1 }
+    pub fn north_east(&self) -> Self {
+        self.compass(1, -1)
     }
-    pub fn south_west(&self) -> Point {
-        *self + Self { y: 1, x: -1 }
+    pub fn south_east(&self) -> Self {
+        self.compass(1, 1)
     }
+    pub fn south_west(&self) -> Self {
+        self.compass(-1, 1)
+    }
+}
+
+/// `v + d`, or None if it does not fit
+#[inline]
+fn shift<T: Coord>(v: T, d: isize) -> Option<T> {
+    let step: T = NumCast::from(d.unsigned_abs())?;
+    match d < 0 {
+        true => v.checked_sub(&step),
+        false => v.checked_add(&step),
+    }
+}
+
+/// `v + i - r`, without going below zero on the way, or None if it does not fit
+#[inline]
+fn towards<T: Coord>(v: T, i: T, r: T) -> Option<T> {
+    match i < r {
+        true => v.checked_sub(&(r - i)),
+        false => v.checked_add(&(i - r)),
+    }
+}
+
+#[inline]
+fn max<T: PartialOrd>(a: T, b: T) -> T {
+    if a >= b { a } else { b }
+}
+
+#[inline]
+fn min<T: PartialOrd>(a: T, b: T) -> T {
+    if a <= b { a } else { b }
 }
 
 impl From<(usize, usize)> for Point {
@@ -134,13 +306,26 @@ impl From<(usize, usize)> for Point {
     }
 }
 
-impl Display for Point {
+impl<T: Display> Display for Point<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "[{},{}]", self.y, self.x)
     }
 }
 
-impl Add for Point {
+/// Parse "x,y", such as "3,-4".  Spaces around the numbers are allowed.
+impl<T: Coord + FromStr> FromStr for Point<T> {
+    type Err = ScanError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = scan_fields(s, "{},{}")?;
+        Ok(Self {
+            x: parse_field(0, fields[0])?,
+            y: parse_field(1, fields[1])?,
+        })
+    }
+}
+
+impl<T: Coord> Add for Point<T> {
     type Output = Self;
     fn add(self, rhs: Self) -> Self::Output {
         Self {
@@ -150,9 +335,9 @@ impl Add for Point {
     }
 }
 
-impl Add<&Point> for Point {
+impl<T: Coord> Add<&Point<T>> for Point<T> {
     type Output = Self;
-    fn add(self, rhs: &Point) -> Point {
+    fn add(self, rhs: &Self) -> Self {
         Self {
             y: self.y + rhs.y,
             x: self.x + rhs.x,
@@ -160,20 +345,20 @@ impl Add<&Point> for Point {
     }
 }
 
-impl AddAssign for Point {
+impl<T: Coord> AddAssign for Point<T> {
     fn add_assign(&mut self, rhs: Self) {
         self.y += rhs.y;
         self.x += rhs.x;
     }
 }
-impl AddAssign<&Point> for Point {
-    fn add_assign(&mut self, rhs: &Point) {
+impl<T: Coord> AddAssign<&Point<T>> for Point<T> {
+    fn add_assign(&mut self, rhs: &Self) {
         self.y += rhs.y;
         self.x += rhs.x;
     }
 }
 
-impl Sub for Point {
+impl<T: Coord> Sub for Point<T> {
     type Output = Self;
     fn sub(self, rhs: Self) -> Self {
         Self {
@@ -183,7 +368,7 @@ impl Sub for Point {
     }
 }
 
-impl Sub<&Point> for Point {
+impl<T: Coord> Sub<&Point<T>> for Point<T> {
     type Output = Self;
     fn sub(self, rhs: &Self) -> Self {
         Self {
@@ -193,21 +378,21 @@ impl Sub<&Point> for Point {
     }
 }
 
-impl SubAssign for Point {
+impl<T: Coord> SubAssign for Point<T> {
     fn sub_assign(&mut self, rhs: Self) {
         self.y -= rhs.y;
         self.x -= rhs.x;
     }
 }
 
-impl SubAssign<&Point> for Point {
+impl<T: Coord> SubAssign<&Point<T>> for Point<T> {
     fn sub_assign(&mut self, rhs: &Self) {
         self.y -= rhs.y;
         self.x -= rhs.x;
     }
 }
 
-impl Rem for Point {
+impl<T: Coord> Rem for Point<T> {
     type Output = Self;
     fn rem(self, rhs: Self) -> Self::Output {
         Self {
@@ -217,7 +402,7 @@ impl Rem for Point {
     }
 }
 
-impl Rem<&Point> for Point {
+impl<T: Coord> Rem<&Point<T>> for Point<T> {
     type Output = Self;
     fn rem(self, rhs: &Self) -> Self::Output {
         Self {
@@ -226,3 +411,109 @@ impl Rem<&Point> for Point {
         }
     }
 }
+
+impl<T: Coord> Zero for Point<T> {
+    fn zero() -> Self {
+        Self {
+            x: T::zero(),
+            y: T::zero(),
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.x.is_zero() && self.y.is_zero()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics() {
+        let a = Point { x: 1, y: 2 };
+        let b = Point { x: 4, y: -2 };
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(a.chebyshev(&b), 4);
+        assert_eq!(a.euclidean_squared(&b), 25);
+        assert_eq!(a.euclidean(&b), 5.0);
+    }
+
+    #[test]
+    fn test_within() {
+        let a = Point { x: 0, y: 0 };
+        let b = Point { x: 3, y: 4 };
+        assert!(a.within(&b, 7, Metric::Manhattan));
+        assert!(!a.within(&b, 6, Metric::Manhattan));
+        assert!(a.within(&b, 4, Metric::Chebyshev));
+        assert!(a.within(&b, 5, Metric::Euclidean));
+        assert!(!a.within(&b, 4, Metric::Euclidean));
+        for metric in [Metric::Manhattan, Metric::Chebyshev, Metric::Euclidean] {
+            assert!(!a.within(&b, -5, metric));
+            assert!(!a.within(&a, -1, metric));
+        }
+    }
+
+    #[test]
+    fn test_manhattan_area() {
+        let center = Point { x: 5, y: 5 };
+        let area: Vec<Point> = center.manhattan_area(2).collect();
+        assert_eq!(area.len(), 13);
+        assert!(area.contains(&center));
+        assert!(area.iter().all(|p| center.manhattan(p) <= 2));
+        assert_eq!(center.manhattan_area(0).count(), 1);
+    }
+
+    #[test]
+    fn test_generic_coords() {
+        let a = Point::<i64>::new(3_000_000_000, -3_000_000_000);
+        let b = Point::<i64>::new(0, 0);
+        assert_eq!(a.manhattan(&b), 6_000_000_000);
+        assert_eq!(b.north(), Point::new(0, -1));
+
+        let small = Point::<u16>::new(2, 7);
+        assert_eq!(small.manhattan(&Point::new(5, 1)), 9);
+        assert!(small.touches(&Point::new(2, 8), false));
+    }
+
+    #[test]
+    fn test_unsigned_neighbors() {
+        let corner = Point::<u16>::new(0, 0);
+        assert_eq!(
+            corner.neighbors(),
+            vec![Point::new(1, 0), Point::new(0, 1), Point::new(1, 1)]
+        );
+        assert_eq!(corner.neighbors_hv().len(), 2);
+        assert_eq!(Point::<u32>::new(4, 4).neighbors().len(), 8);
+        assert_eq!(corner.south_east(), Point::new(1, 1));
+        assert_eq!(corner.checked_offset(0, -1), None);
+        assert_eq!(Point::<u8>::new(255, 3).neighbors_hv().len(), 3);
+
+        let area: Vec<_> = Point::<u32>::new(1, 0).manhattan_area(2).collect();
+        assert_eq!(area.len(), 8);
+        assert!(area.iter().all(|p| p.manhattan(&Point::new(1, 0)) <= 2));
+        assert_eq!(Point::<u32>::new(5, 5).manhattan_area(2).count(), 13);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_unsigned_compass_underflow() {
+        Point::<u16>::new(3, 0).north();
+    }
+
+    #[test]
+    fn test_cast() {
+        let p = Point::<i32>::new(-4, 9);
+        assert_eq!(p.cast::<i64>(), Point::<i64>::new(-4, 9));
+        assert_eq!(p.try_cast::<isize>(), Ok(Point::new(-4, 9)));
+        assert!(p.try_cast::<u8>().is_err());
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("3,-4".parse::<Point>(), Ok(Point::new(3, -4)));
+        assert_eq!(" 10, 2 ".parse::<Point<u8>>(), Ok(Point::new(10, 2)));
+        assert!("3;4".parse::<Point>().is_err());
+        assert!("3,x".parse::<Point>().is_err());
+    }
+}
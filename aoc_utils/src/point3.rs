@@ -0,0 +1,185 @@
+use std::fmt::Display;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+static NEIGHBORS_6: [Point3; 6] = [
+    Point3 { x: -1, y: 0, z: 0 },
+    Point3 { x: 1, y: 0, z: 0 },
+    Point3 { x: 0, y: -1, z: 0 },
+    Point3 { x: 0, y: 1, z: 0 },
+    Point3 { x: 0, y: 0, z: -1 },
+    Point3 { x: 0, y: 0, z: 1 },
+];
+
+#[derive(Debug, PartialEq, Clone, Copy, Hash, Eq, Default, PartialOrd, Ord)]
+pub struct Point3 {
+    pub x: isize,
+    pub y: isize,
+    pub z: isize,
+}
+
+impl Point3 {
+    pub fn new(x: isize, y: isize, z: isize) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn mul(&self, factor: isize) -> Self {
+        Self {
+            x: self.x * factor,
+            y: self.y * factor,
+            z: self.z * factor,
+        }
+    }
+
+    /// Sum of the absolute differences on each axis
+    #[inline]
+    pub fn manhattan(&self, other: &Self) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    /// The 6 points that share a face with this one
+    pub fn neighbors_6(&self) -> impl Iterator<Item = Point3> + use<> {
+        let p = *self;
+        NEIGHBORS_6.iter().map(move |dir| p + *dir)
+    }
+
+    /// The 26 points that share a face, edge or corner with this one
+    pub fn neighbors_26(&self) -> impl Iterator<Item = Point3> + use<> {
+        let p = *self;
+        (-1..=1)
+            .flat_map(|z| (-1..=1).flat_map(move |y| (-1..=1).map(move |x| Point3 { x, y, z })))
+            .filter(|dir| *dir != Point3::default())
+            .map(move |dir| p + dir)
+    }
+}
+
+impl From<(isize, isize, isize)> for Point3 {
+    fn from(value: (isize, isize, isize)) -> Self {
+        Self {
+            x: value.0,
+            y: value.1,
+            z: value.2,
+        }
+    }
+}
+
+impl From<(usize, usize, usize)> for Point3 {
+    fn from(value: (usize, usize, usize)) -> Self {
+        Self {
+            x: value.0 as isize,
+            y: value.1 as isize,
+            z: value.2 as isize,
+        }
+    }
+}
+
+impl Display for Point3 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{},{},{}]", self.x, self.y, self.z)
+    }
+}
+
+impl Add for Point3 {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+            z: self.z + rhs.z,
+        }
+    }
+}
+
+impl Add<&Point3> for Point3 {
+    type Output = Self;
+    fn add(self, rhs: &Point3) -> Self::Output {
+        self + *rhs
+    }
+}
+
+impl AddAssign for Point3 {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+    }
+}
+
+impl AddAssign<&Point3> for Point3 {
+    fn add_assign(&mut self, rhs: &Point3) {
+        *self += *rhs;
+    }
+}
+
+impl Sub for Point3 {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+            z: self.z - rhs.z,
+        }
+    }
+}
+
+impl Sub<&Point3> for Point3 {
+    type Output = Self;
+    fn sub(self, rhs: &Point3) -> Self::Output {
+        self - *rhs
+    }
+}
+
+impl SubAssign for Point3 {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+    }
+}
+
+impl SubAssign<&Point3> for Point3 {
+    fn sub_assign(&mut self, rhs: &Point3) {
+        *self -= *rhs;
+    }
+}
+
+impl Neg for Point3 {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manhattan() {
+        let a = Point3::new(1, -2, 3);
+        let b = Point3::new(-1, 2, 0);
+        assert_eq!(a.manhattan(&b), 9);
+        assert_eq!(b.manhattan(&a), 9);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let p = Point3::new(1, 1, 1);
+        assert_eq!(p.neighbors_6().count(), 6);
+        assert!(p.neighbors_6().all(|n| n.manhattan(&p) == 1));
+        assert_eq!(p.neighbors_26().count(), 26);
+        assert!(!p.neighbors_26().any(|n| n == p));
+    }
+
+    #[test]
+    fn test_ops() {
+        let mut p = Point3::new(1, 2, 3);
+        p += Point3::new(1, 1, 1);
+        assert_eq!(p, Point3::new(2, 3, 4));
+        assert_eq!(p - Point3::new(2, 3, 4), Point3::default());
+        assert_eq!(-p, p.mul(-1));
+    }
+}
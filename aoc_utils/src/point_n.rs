@@ -0,0 +1,235 @@
+use std::fmt::Display;
+use std::ops::{Add, AddAssign, Index, IndexMut, Sub, SubAssign};
+
+use crate::{Point, Point3};
+
+/// A point with `D` dimensions.  For 2D and 3D, `Point` and `Point3`
+/// are easier to work with, and both convert to and from `PointN`.
+#[derive(Debug, PartialEq, Clone, Copy, Hash, Eq, PartialOrd, Ord)]
+pub struct PointN<const D: usize>(pub [isize; D]);
+
+impl<const D: usize> PointN<D> {
+    pub fn new(coords: [isize; D]) -> Self {
+        Self(coords)
+    }
+
+    /// Sum of the absolute differences on each axis
+    pub fn manhattan(&self, other: &Self) -> usize {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .map(|(a, b)| a.abs_diff(*b))
+            .sum()
+    }
+
+    /// Largest absolute difference on any one axis
+    pub fn chebyshev(&self, other: &Self) -> usize {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .map(|(a, b)| a.abs_diff(*b))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The `2 * D` points one step away along a single axis
+    pub fn neighbors_orthogonal(&self) -> impl Iterator<Item = Self> + use<D> {
+        let p = *self;
+        (0..D).flat_map(move |axis| {
+            [-1, 1].into_iter().map(move |delta| {
+                let mut n = p;
+                n.0[axis] += delta;
+                n
+            })
+        })
+    }
+
+    /// The `3^D - 1` points that touch this one, including diagonally
+    pub fn neighbors(&self) -> impl Iterator<Item = Self> + use<D> {
+        let p = *self;
+        let count = 3usize.pow(D as u32);
+        (0..count)
+            .filter(move |&i| i != count / 2)
+            .map(move |mut i| {
+                let mut n = p;
+                for axis in 0..D {
+                    n.0[axis] += (i % 3) as isize - 1;
+                    i /= 3;
+                }
+                n
+            })
+    }
+}
+
+impl<const D: usize> Default for PointN<D> {
+    fn default() -> Self {
+        Self([0; D])
+    }
+}
+
+impl<const D: usize> Index<usize> for PointN<D> {
+    type Output = isize;
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+
+impl<const D: usize> IndexMut<usize> for PointN<D> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.0[index]
+    }
+}
+
+impl<const D: usize> From<[isize; D]> for PointN<D> {
+    fn from(value: [isize; D]) -> Self {
+        Self(value)
+    }
+}
+
+/// Lifts a 2D point into `D` dimensions, as `[x, y, 0, ...]`.
+/// `D` must be at least 2, so no axis is lost; smaller `D` fail to compile:
+/// ```compile_fail
+/// use aoc_utils::{Point, PointN};
+/// let p = PointN::<1>::from(Point::new(3, 4));
+/// ```
+impl<const D: usize> From<Point> for PointN<D> {
+    fn from(value: Point) -> Self {
+        const { assert!(D >= 2, "a Point needs at least 2 dimensions") };
+        let mut p = Self::default();
+        p.0[0] = value.x;
+        p.0[1] = value.y;
+        p
+    }
+}
+
+impl From<PointN<2>> for Point {
+    fn from(value: PointN<2>) -> Self {
+        Self {
+            x: value.0[0],
+            y: value.0[1],
+        }
+    }
+}
+
+impl From<Point3> for PointN<3> {
+    fn from(value: Point3) -> Self {
+        Self([value.x, value.y, value.z])
+    }
+}
+
+impl From<PointN<3>> for Point3 {
+    fn from(value: PointN<3>) -> Self {
+        Self {
+            x: value.0[0],
+            y: value.0[1],
+            z: value.0[2],
+        }
+    }
+}
+
+impl<const D: usize> Display for PointN<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[")?;
+        for (i, v) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", v)?;
+        }
+        write!(f, "]")
+    }
+}
+
+impl<const D: usize> Add for PointN<D> {
+    type Output = Self;
+    fn add(mut self, rhs: Self) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl<const D: usize> Add<&PointN<D>> for PointN<D> {
+    type Output = Self;
+    fn add(self, rhs: &Self) -> Self::Output {
+        self + *rhs
+    }
+}
+
+impl<const D: usize> AddAssign for PointN<D> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0.iter_mut().zip(rhs.0).for_each(|(a, b)| *a += b);
+    }
+}
+
+impl<const D: usize> AddAssign<&PointN<D>> for PointN<D> {
+    fn add_assign(&mut self, rhs: &Self) {
+        *self += *rhs;
+    }
+}
+
+impl<const D: usize> Sub for PointN<D> {
+    type Output = Self;
+    fn sub(mut self, rhs: Self) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+impl<const D: usize> Sub<&PointN<D>> for PointN<D> {
+    type Output = Self;
+    fn sub(self, rhs: &Self) -> Self::Output {
+        self - *rhs
+    }
+}
+
+impl<const D: usize> SubAssign for PointN<D> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0.iter_mut().zip(rhs.0).for_each(|(a, b)| *a -= b);
+    }
+}
+
+impl<const D: usize> SubAssign<&PointN<D>> for PointN<D> {
+    fn sub_assign(&mut self, rhs: &Self) {
+        *self -= *rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neighbors() {
+        let p = PointN::<4>::default();
+        assert_eq!(p.neighbors().count(), 80);
+        assert!(p.neighbors().all(|n| n.chebyshev(&p) == 1));
+        assert_eq!(p.neighbors_orthogonal().count(), 8);
+        assert!(p.neighbors_orthogonal().all(|n| n.manhattan(&p) == 1));
+    }
+
+    #[test]
+    fn test_distance() {
+        let a = PointN::new([1, -2, 3]);
+        let b = PointN::new([-1, 2, 0]);
+        assert_eq!(a.manhattan(&b), 9);
+        assert_eq!(a.chebyshev(&b), 4);
+    }
+
+    #[test]
+    fn test_point_conversions() {
+        let p = Point { x: 3, y: -4 };
+        assert_eq!(PointN::<2>::from(p), PointN::new([3, -4]));
+        assert_eq!(PointN::<4>::from(p), PointN::new([3, -4, 0, 0]));
+        assert_eq!(Point::from(PointN::<2>::from(p)), p);
+        let p3 = Point3::new(1, 2, 3);
+        assert_eq!(Point3::from(PointN::from(p3)), p3);
+    }
+
+    #[test]
+    fn test_ops() {
+        let a = PointN::new([1, 2, 3, 4]);
+        let b = PointN::new([4, 3, 2, 1]);
+        assert_eq!(a + b, PointN::new([5; 4]));
+        assert_eq!((a + b) - b, a);
+    }
+}
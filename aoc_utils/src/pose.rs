@@ -0,0 +1,97 @@
+use std::fmt::Display;
+
+use crate::{Direction, Point};
+
+/// A position and the direction it is facing, such as a guard on patrol.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Pose {
+    pub pos: Point,
+    pub heading: Direction,
+}
+
+impl Pose {
+    pub fn new(pos: Point, heading: Direction) -> Self {
+        Self { pos, heading }
+    }
+
+    /// The point one step ahead, without moving
+    pub fn ahead(&self) -> Point {
+        self.pos + self.heading
+    }
+
+    /// Take one step in the direction of `heading`
+    pub fn forward(&self) -> Self {
+        self.step(1)
+    }
+
+    /// Take `n` steps in the direction of `heading`
+    pub fn step(&self, n: usize) -> Self {
+        Self {
+            pos: self.pos.step(self.heading, n),
+            ..*self
+        }
+    }
+
+    /// Turn left to the next major direction, without moving
+    pub fn turn_left(&self) -> Self {
+        Self {
+            heading: self.heading.left(),
+            ..*self
+        }
+    }
+
+    /// Turn right to the next major direction, without moving
+    pub fn turn_right(&self) -> Self {
+        Self {
+            heading: self.heading.right(),
+            ..*self
+        }
+    }
+
+    /// Turn 180 degrees, without moving
+    pub fn turn_around(&self) -> Self {
+        Self {
+            heading: self.heading.turn_around(),
+            ..*self
+        }
+    }
+}
+
+impl Display for Pose {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.pos, self.heading)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_walk_square() {
+        let start = Pose::new(Point { x: 0, y: 0 }, Direction::North);
+        let mut pose = start;
+        for _ in 0..4 {
+            pose = pose.forward().forward().turn_right();
+        }
+        assert_eq!(pose, start);
+    }
+
+    #[test]
+    fn test_turns() {
+        let pose = Pose::new(Point { x: 2, y: 2 }, Direction::East);
+        assert_eq!(pose.turn_left().heading, Direction::North);
+        assert_eq!(pose.turn_right().heading, Direction::South);
+        assert_eq!(pose.turn_around().heading, Direction::West);
+        assert_eq!(pose.ahead(), Point { x: 3, y: 2 });
+        assert_eq!(pose.step(3).pos, Point { x: 5, y: 2 });
+    }
+
+    #[test]
+    fn test_diagonal_turns() {
+        let pose = Pose::new(Point { x: 0, y: 0 }, Direction::NorthEast);
+        assert_eq!(pose.turn_left().heading, Direction::NorthWest);
+        assert_eq!(pose.turn_right().heading, Direction::SouthEast);
+        assert_eq!(pose.turn_left().turn_left().heading, Direction::SouthWest);
+    }
+}
@@ -0,0 +1,362 @@
+//! Sets of integers stored as sorted, non-overlapping ranges.
+//!
+//! Handy whenever a puzzle deals in huge spans of numbers, such as seed maps
+//! or sensor coverage, where storing each number would be far too slow.
+use num::PrimInt;
+use std::fmt::Display;
+use std::ops::{Bound, RangeBounds, RangeInclusive};
+
+/// Convert any range to an inclusive range.  Unbounded ends become the
+/// min or max of `T`.  Returns None if the range is empty.
+fn inclusive<T: PrimInt>(range: impl RangeBounds<T>) -> Option<RangeInclusive<T>> {
+    let start = match range.start_bound() {
+        Bound::Included(s) => *s,
+        Bound::Excluded(s) => s.checked_add(&T::one())?,
+        Bound::Unbounded => T::min_value(),
+    };
+    let end = match range.end_bound() {
+        Bound::Included(e) => *e,
+        Bound::Excluded(e) => e.checked_sub(&T::one())?,
+        Bound::Unbounded => T::max_value(),
+    };
+    (start <= end).then_some(start..=end)
+}
+
+/// Number of integers in `start..=end`, which always fits a `u128`
+/// unless the range covers every 128 bit integer
+fn count<T: PrimInt>(range: &RangeInclusive<T>) -> Option<u128> {
+    let span = match (range.start().to_u128(), range.end().to_u128()) {
+        (Some(s), Some(e)) => e - s,
+        // A negative start, so both ends fit an i128
+        _ => {
+            let s = range.start().to_i128().expect("signed start fits i128");
+            let e = range.end().to_i128().expect("signed end fits i128");
+            e.wrapping_sub(s) as u128
+        }
+    };
+    span.checked_add(1)
+}
+
+/// A set of integers, stored as sorted, disjoint, inclusive ranges, so
+/// that the max of `T` can be held.  Touching ranges are merged.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct RangeSet<T: PrimInt> {
+    ranges: Vec<RangeInclusive<T>>,
+}
+
+impl<T: PrimInt> RangeSet<T> {
+    pub fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    /// The ranges in the set, in order
+    pub fn ranges(&self) -> &[RangeInclusive<T>] {
+        &self.ranges
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, RangeInclusive<T>> {
+        self.ranges.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Total number of integers in the set.
+    /// Panics if that doesn't fit a `u128`, which needs a set of 128 bit
+    /// integers that covers every value.
+    pub fn len(&self) -> u128 {
+        self.ranges
+            .iter()
+            .try_fold(0u128, |acc, r| acc.checked_add(count(r)?))
+            .expect("RangeSet length does not fit a u128")
+    }
+
+    /// True if `value` is in the set
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|r| *r.end() < value);
+        self.ranges.get(i).is_some_and(|r| *r.start() <= value)
+    }
+
+    /// The range in the set that holds `value`, if any
+    pub fn range_of(&self, value: T) -> Option<RangeInclusive<T>> {
+        let i = self.ranges.partition_point(|r| *r.end() < value);
+        self.ranges.get(i).filter(|r| *r.start() <= value).cloned()
+    }
+
+    /// Add a range, merging it with any ranges it overlaps or touches
+    pub fn insert(&mut self, range: impl RangeBounds<T>) {
+        let Some(range) = inclusive(range) else {
+            return;
+        };
+        let (mut start, mut end) = range.into_inner();
+        let one = T::one();
+        let first = self
+            .ranges
+            .partition_point(|r| r.end().saturating_add(one) < start);
+        let last = self
+            .ranges
+            .partition_point(|r| *r.start() <= end.saturating_add(one));
+        if first < last {
+            start = start.min(*self.ranges[first].start());
+            end = end.max(*self.ranges[last - 1].end());
+        }
+        self.ranges.splice(first..last, [start..=end]);
+    }
+
+    /// Remove a range, splitting any range that it cuts through
+    pub fn remove(&mut self, range: impl RangeBounds<T>) {
+        let Some(range) = inclusive(range) else {
+            return;
+        };
+        let (start, end) = range.into_inner();
+        let first = self.ranges.partition_point(|r| *r.end() < start);
+        let last = self.ranges.partition_point(|r| *r.start() <= end);
+        if first >= last {
+            return;
+        }
+        let mut keep = Vec::with_capacity(2);
+        if *self.ranges[first].start() < start {
+            keep.push(*self.ranges[first].start()..=start - T::one());
+        }
+        if *self.ranges[last - 1].end() > end {
+            keep.push(end + T::one()..=*self.ranges[last - 1].end());
+        }
+        self.ranges.splice(first..last, keep);
+    }
+
+    /// Every integer in either set
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for r in &other.ranges {
+            result.insert(r.clone());
+        }
+        result
+    }
+
+    /// Every integer in this set that is not in `other`
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for r in &other.ranges {
+            result.remove(r.clone());
+        }
+        result
+    }
+
+    /// Every integer in both sets
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let start = *a.start().max(b.start());
+            let end = *a.end().min(b.end());
+            if start <= end {
+                ranges.push(start..=end);
+            }
+            match a.end() < b.end() {
+                true => i += 1,
+                false => j += 1,
+            }
+        }
+        Self { ranges }
+    }
+
+    /// Every integer within `bounds` that is not in the set
+    pub fn complement(&self, bounds: impl RangeBounds<T>) -> Self {
+        let mut result = Self::new();
+        result.insert(bounds);
+        result.difference(self)
+    }
+}
+
+impl<T: PrimInt, R: RangeBounds<T>> FromIterator<R> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for r in iter {
+            set.insert(r);
+        }
+        set
+    }
+}
+
+impl<T: PrimInt, R: RangeBounds<T>> Extend<R> for RangeSet<T> {
+    fn extend<I: IntoIterator<Item = R>>(&mut self, iter: I) {
+        for r in iter {
+            self.insert(r);
+        }
+    }
+}
+
+impl<T: PrimInt + Display> Display for RangeSet<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{")?;
+        for (i, r) in self.ranges.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}..={}", r.start(), r.end())?;
+        }
+        write!(f, "}}")
+    }
+}
+
+/// A piecewise mapping of numbers, such as an almanac seed map.
+/// Each entry shifts a source range to a destination.  Numbers not covered
+/// by any entry map to themselves.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RangeMap<T: PrimInt> {
+    /// `(source range, destination start)`, sorted by source start
+    entries: Vec<(RangeInclusive<T>, T)>,
+}
+
+impl<T: PrimInt> RangeMap<T> {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Map `len` numbers starting at `source` to those starting at `dest`.
+    /// This is the order used by almanac lines: `dest source len`.
+    pub fn insert(&mut self, dest: T, source: T, len: T) {
+        if len.is_zero() {
+            return;
+        }
+        let i = self.entries.partition_point(|(r, _)| *r.start() < source);
+        self.entries
+            .insert(i, (source..=source + (len - T::one()), dest));
+    }
+
+    /// Push a single number through the map
+    pub fn map(&self, value: T) -> T {
+        self.entries
+            .iter()
+            .find(|(r, _)| r.contains(&value))
+            .map_or(value, |(r, dest)| value - *r.start() + *dest)
+    }
+
+    /// Push every range in `set` through the map, splitting ranges that
+    /// straddle more than one entry
+    pub fn map_set(&self, set: &RangeSet<T>) -> RangeSet<T> {
+        let mut result = RangeSet::new();
+        for range in set.iter() {
+            let (mut start, end) = (*range.start(), *range.end());
+            let mut done = false;
+            for (source, dest) in &self.entries {
+                let (s, e) = (*source.start(), *source.end());
+                if e < start {
+                    continue;
+                }
+                if s > end {
+                    break;
+                }
+                // The gap before this entry maps to itself
+                if s > start {
+                    result.insert(start..=s - T::one());
+                    start = s;
+                }
+                let stop = e.min(end);
+                result.insert(start - s + *dest..=stop - s + *dest);
+                if stop == end {
+                    done = true;
+                    break;
+                }
+                start = stop + T::one();
+            }
+            if !done {
+                result.insert(start..=end);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_merges() {
+        let mut set = RangeSet::new();
+        set.insert(3..=5);
+        set.insert(10..=14);
+        set.insert(16..=20);
+        set.insert(12..=18);
+        assert_eq!(set.ranges(), &[3..=5, 10..=20]);
+        assert_eq!(set.len(), 14);
+        set.insert(6..10);
+        assert_eq!(set.ranges(), &[3..=20]);
+    }
+
+    #[test]
+    fn test_contains() {
+        let set: RangeSet<u64> = [3..=5, 10..=14].into_iter().collect();
+        assert!(set.contains(5));
+        assert!(!set.contains(6));
+        assert!(set.contains(10));
+        assert!(!set.contains(15));
+        assert_eq!(set.range_of(12), Some(10..=14));
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut set: RangeSet<i32> = [0..10, 20..30].into_iter().collect();
+        set.remove(5..25);
+        assert_eq!(set.ranges(), &[0..=4, 25..=29]);
+        set.remove(2..=2);
+        assert_eq!(set.ranges(), &[0..=1, 3..=4, 25..=29]);
+    }
+
+    #[test]
+    fn test_set_ops() {
+        let a: RangeSet<i32> = [0..10, 20..30].into_iter().collect();
+        let b: RangeSet<i32> = [5..25].into_iter().collect();
+        assert_eq!(a.intersection(&b).ranges(), &[5..=9, 20..=24]);
+        assert_eq!(a.difference(&b).ranges(), &[0..=4, 25..=29]);
+        assert_eq!(a.union(&b).ranges(), &[0..=29]);
+        assert_eq!(a.complement(-5..35).ranges(), &[-5..=-1, 10..=19, 30..=34]);
+    }
+
+    #[test]
+    fn test_type_max() {
+        let mut set = RangeSet::<u8>::new();
+        set.insert(250..=255);
+        assert!(set.contains(255));
+        assert_eq!(set.len(), 6);
+        assert_eq!(set.complement(..=u8::MAX).ranges(), &[0..=249]);
+        set.remove(255..);
+        assert_eq!(set.ranges(), &[250..=254]);
+        assert_eq!(RangeSet::<u8>::from_iter([..]).ranges(), &[0..=255]);
+        // An excluded end at the min is empty, not a wrap
+        assert!(RangeSet::<u8>::from_iter([..0]).is_empty());
+    }
+
+    #[test]
+    fn test_full_width_len() {
+        let set: RangeSet<i8> = [-128..=127].into_iter().collect();
+        assert_eq!(set.len(), 256);
+        let set: RangeSet<u64> = [..].into_iter().collect();
+        assert_eq!(set.len(), 1 << 64);
+        let set: RangeSet<i128> = [i128::MIN..=-1, 1..=i128::MAX].into_iter().collect();
+        assert_eq!(set.len(), u128::MAX);
+    }
+
+    #[test]
+    fn test_map() {
+        let mut map = RangeMap::new();
+        map.insert(50, 98, 2);
+        map.insert(52, 50, 48);
+        assert_eq!(map.map(79), 81);
+        assert_eq!(map.map(14), 14);
+        assert_eq!(map.map(99), 51);
+
+        let seeds: RangeSet<u64> = [79..79 + 14, 55..55 + 13].into_iter().collect();
+        let soil = map.map_set(&seeds);
+        assert_eq!(soil.ranges(), &[57..=69, 81..=94]);
+
+        let all: RangeSet<u64> = [40..110].into_iter().collect();
+        assert_eq!(map.map_set(&all).ranges(), &[40..=109]);
+    }
+}
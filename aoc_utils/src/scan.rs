@@ -0,0 +1,194 @@
+//! Scanf style extraction for lines such as `p=0,4 v=3,-3`.
+//!
+//! Use the `scan!` macro, which splits the line with `scan_fields` and then
+//! parses each field with `FromStr`.
+use std::any::type_name;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ScanError {
+    #[error("expected {expected:?} at column {column}, found {found:?}")]
+    Literal {
+        expected: String,
+        column: usize,
+        found: String,
+    },
+    #[error("expected {expected:?} after column {column}")]
+    Missing { expected: String, column: usize },
+    #[error("field {index} at column {column}: {text:?} is not a valid {type_name}")]
+    Field {
+        index: usize,
+        column: usize,
+        text: String,
+        type_name: &'static str,
+    },
+}
+
+/// A `{}` field cut out of the line, with its column (counting from 1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanField<'a> {
+    pub text: &'a str,
+    pub column: usize,
+}
+
+/// Split `line` using `pattern`, where each `{}` is a field and everything
+/// else must match exactly.  A field runs up to the first place that the
+/// following text matches, or to the end of the line.  Fields are trimmed.
+///
+/// Panics if two fields are next to each other, as there is no way to tell
+/// where one ends.
+pub fn scan_fields<'a>(line: &'a str, pattern: &str) -> Result<Vec<ScanField<'a>>, ScanError> {
+    assert!(
+        !pattern.contains("{}{}"),
+        "fields in {:?} must be separated by text",
+        pattern
+    );
+    let mut literals = pattern.split("{}");
+    let first = literals.next().unwrap_or_default();
+    let mut rest = expect_literal(line, line, first)?;
+    let mut fields = Vec::new();
+    for literal in literals {
+        let column = column(line, rest);
+        // Only the last field can be followed by nothing
+        let end = match literal.is_empty() {
+            true => rest.len(),
+            false => rest.find(literal).ok_or_else(|| ScanError::Missing {
+                expected: literal.to_string(),
+                column,
+            })?,
+        };
+        fields.push(ScanField {
+            text: rest[..end].trim(),
+            column,
+        });
+        rest = expect_literal(line, &rest[end..], literal)?;
+    }
+    match rest.is_empty() {
+        true => Ok(fields),
+        false => Err(ScanError::Literal {
+            expected: "end of line".to_string(),
+            column: column(line, rest),
+            found: rest.to_string(),
+        }),
+    }
+}
+
+/// Strip `literal` from the start of `rest`
+fn expect_literal<'a>(line: &str, rest: &'a str, literal: &str) -> Result<&'a str, ScanError> {
+    rest.strip_prefix(literal)
+        .ok_or_else(|| ScanError::Literal {
+            expected: literal.to_string(),
+            column: column(line, rest),
+            found: rest.chars().take(literal.chars().count()).collect(),
+        })
+}
+
+/// Column of `rest`, which is a suffix of `line`, counting from 1
+fn column(line: &str, rest: &str) -> usize {
+    line[..line.len() - rest.len()].chars().count() + 1
+}
+
+/// Parse the field at `index` to T, for the `scan!` macro
+pub fn parse_field<T: FromStr>(index: usize, field: ScanField) -> Result<T, ScanError> {
+    field.text.parse().map_err(|_| ScanError::Field {
+        index,
+        column: field.column,
+        text: field.text.to_string(),
+        type_name: type_name::<T>(),
+    })
+}
+
+/// Extract typed fields from a line, scanf style.  Each `{}` in the pattern
+/// is parsed to the matching type with `FromStr`.  Returns a tuple, or a
+/// `ScanError` that says which part of the line didn't match.
+///
+/// Example:
+/// ```
+/// # use aoc_utils::scan;
+/// let (px, py, vx, vy) = scan!("p=0,4 v=3,-3", "p={},{} v={},{}" => i64, i64, i64, i64)
+///     .expect("failed to scan");
+/// assert_eq!((px, py, vx, vy), (0, 4, 3, -3));
+/// ```
+#[macro_export]
+macro_rules! scan {
+    ($line:expr, $pattern:expr => $($t:ty),+ $(,)?) => {
+        $crate::scan::scan_fields($line, $pattern).and_then(|fields| {
+            let count = [$(stringify!($t)),+].len();
+            assert_eq!(
+                fields.len(),
+                count,
+                "pattern {:?} has {} fields, but {} types were given",
+                $pattern,
+                fields.len(),
+                count
+            );
+            let mut fields = fields.into_iter().enumerate();
+            Ok(($({
+                let (index, field) = fields.next().expect("counted above");
+                $crate::scan::parse_field::<$t>(index, field)?
+            },)+))
+        })
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan() {
+        let (x, y) = scan!("Button A: X+94, Y+34", "Button A: X{}, Y{}" => i64, i64).unwrap();
+        assert_eq!((x, y), (94, 34));
+        let (name, value) = scan!("Register A: 729", "Register {}: {}" => char, u64).unwrap();
+        assert_eq!((name, value), ('A', 729));
+        // A field at the end takes the rest of the line
+        let (word,) = scan!("move to the end", "move {}" => String).unwrap();
+        assert_eq!(word, "to the end");
+    }
+
+    #[test]
+    fn test_scan_errors() {
+        let result = scan!("p=0,4 x=3,-3", "p={},{} v={},{}" => i64, i64, i64, i64);
+        assert_eq!(
+            result,
+            Err(ScanError::Missing {
+                expected: " v=".to_string(),
+                column: 5,
+            })
+        );
+        let result = scan!("p=0,four", "p={},{}" => i64, i64);
+        let err = result.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "field 1 at column 5: \"four\" is not a valid i64"
+        );
+        let result = scan!("a=1 extra", "a={} " => u8);
+        assert!(matches!(result, Err(ScanError::Literal { column: 5, .. })));
+        let result = scan!("b=1", "a={}" => u8);
+        assert!(matches!(result, Err(ScanError::Literal { column: 1, .. })));
+    }
+
+    #[derive(Debug, PartialEq, crate::FromLine)]
+    #[aoc(format = "p={pos} v={vel}")]
+    struct Robot {
+        pos: crate::Point,
+        vel: crate::Point,
+    }
+
+    #[test]
+    fn test_derive() {
+        let robot: Robot = "p=0,4 v=3,-3".parse().unwrap();
+        assert_eq!(robot.pos, crate::Point::new(0, 4));
+        assert_eq!(robot.vel, crate::Point::new(3, -3));
+        let err = "p=0,4 v=3,x".parse::<Robot>().unwrap_err();
+        assert!(matches!(err, ScanError::Field { index: 1, .. }));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_scan_wrong_count() {
+        let _ = scan!("1,2", "{},{}" => u8);
+    }
+}
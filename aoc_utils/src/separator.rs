@@ -0,0 +1,88 @@
+//! What to split a line on, without the nightly `Pattern` trait.
+//!
+//! Implemented for `char`, `&str`, `&[char]`, `[char; N]`, and closures
+//! such as `|c: char| c == ',' || c.is_whitespace()`.
+
+/// Something that can be found in a string, to split it
+pub trait Separator {
+    /// The byte range of the first match in `s`
+    fn find_in(&self, s: &str) -> Option<(usize, usize)>;
+}
+
+impl Separator for char {
+    fn find_in(&self, s: &str) -> Option<(usize, usize)> {
+        s.find(*self).map(|i| (i, i + self.len_utf8()))
+    }
+}
+
+/// An empty string never matches
+impl Separator for &str {
+    fn find_in(&self, s: &str) -> Option<(usize, usize)> {
+        match self.is_empty() {
+            true => None,
+            false => s.find(*self).map(|i| (i, i + self.len())),
+        }
+    }
+}
+
+impl Separator for &[char] {
+    fn find_in(&self, s: &str) -> Option<(usize, usize)> {
+        find_char(s, |c| self.contains(&c))
+    }
+}
+
+impl<const N: usize> Separator for [char; N] {
+    fn find_in(&self, s: &str) -> Option<(usize, usize)> {
+        find_char(s, |c| self.contains(&c))
+    }
+}
+
+impl<F: Fn(char) -> bool> Separator for F {
+    fn find_in(&self, s: &str) -> Option<(usize, usize)> {
+        find_char(s, self)
+    }
+}
+
+fn find_char(s: &str, f: impl Fn(char) -> bool) -> Option<(usize, usize)> {
+    s.char_indices()
+        .find(|(_, c)| f(*c))
+        .map(|(i, c)| (i, i + c.len_utf8()))
+}
+
+/// Split `s` on every match of `sep`, like `str::split`
+pub fn split_on<'a, S: Separator + ?Sized>(s: &'a str, sep: &S) -> impl Iterator<Item = &'a str> {
+    let mut rest = Some(s);
+    std::iter::from_fn(move || {
+        let current = rest?;
+        match sep.find_in(current) {
+            Some((start, end)) => {
+                rest = Some(&current[end..]);
+                Some(&current[..start])
+            }
+            None => {
+                rest = None;
+                Some(current)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split<S: Separator>(s: &str, sep: S) -> Vec<&str> {
+        split_on(s, &sep).collect()
+    }
+
+    #[test]
+    fn test_split_on() {
+        assert_eq!(split("1,2,,3", ','), ["1", "2", "", "3"]);
+        assert_eq!(split("a -> b -> c", " -> "), ["a", "b", "c"]);
+        assert_eq!(split("1,2 3", [',', ' ']), ["1", "2", "3"]);
+        assert_eq!(split("1,2 3", &[',', ' '][..]), ["1", "2", "3"]);
+        assert_eq!(split("1\t2", |c: char| c.is_whitespace()), ["1", "2"]);
+        assert_eq!(split("", ','), [""]);
+        assert_eq!(split("abc", ""), ["abc"]);
+    }
+}
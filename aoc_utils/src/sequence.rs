@@ -0,0 +1,120 @@
+//! Extrapolate integer sequences, and fit polynomials through samples.
+//!
+//! Works by repeated differences (each row is the gaps between the row
+//! above), or exact Lagrange interpolation when the samples are spaced out.
+use num::{Num, Zero};
+
+use crate::Rational128;
+
+/// Each row is the differences of the row before, down to the first row
+/// that is all zeros (or a single value)
+fn difference_rows<T: Num + Copy>(seq: &[T]) -> Vec<Vec<T>> {
+    let mut rows = vec![seq.to_vec()];
+    loop {
+        let last = rows.last().expect("there is always a row");
+        if last.len() <= 1 || last.iter().all(|v| v.is_zero()) {
+            return rows;
+        }
+        let next = last.windows(2).map(|w| w[1] - w[0]).collect();
+        rows.push(next);
+    }
+}
+
+/// The next value of the sequence, by finite differences.
+/// `[0, 3, 6, 9, 12, 15]` gives `18`.  An empty sequence gives zero.
+pub fn extrapolate_next<T: Num + Copy>(seq: &[T]) -> T {
+    difference_rows(seq)
+        .iter()
+        .filter_map(|row| row.last())
+        .fold(T::zero(), |acc, v| acc + *v)
+}
+
+/// The value before the start of the sequence, by finite differences.
+/// `[10, 13, 16, 21, 30, 45]` gives `5`.  An empty sequence gives zero.
+pub fn extrapolate_prev<T: Num + Copy>(seq: &[T]) -> T {
+    difference_rows(seq)
+        .iter()
+        .rev()
+        .filter_map(|row| row.first())
+        .fold(T::zero(), |acc, v| *v - acc)
+}
+
+/// The degree of the polynomial that generates the sequence: the number of
+/// times it has to be differenced to become constant.
+/// Returns None if there aren't enough values to be sure.  Needs at least
+/// `degree + 2` values.  A sequence of zeros has degree 0, and an empty
+/// sequence has none.
+pub fn polynomial_degree<T: Num + Copy>(seq: &[T]) -> Option<usize> {
+    if seq.is_empty() {
+        return None;
+    }
+    let rows = difference_rows(seq);
+    let last = rows.last()?;
+    if !last.iter().all(|v| v.is_zero()) {
+        return None;
+    }
+    // The row above the zeros is constant
+    Some(rows.len().saturating_sub(2))
+}
+
+/// Evaluate, at `x`, the polynomial that passes through every `(x, y)`
+/// sample, using exact Lagrange interpolation.
+/// Panics if two samples share the same `x`.
+pub fn interpolate(samples: &[(i128, i128)], x: i128) -> Rational128 {
+    samples
+        .iter()
+        .enumerate()
+        .map(|(i, &(xi, yi))| {
+            samples.iter().enumerate().filter(|(j, _)| *j != i).fold(
+                Rational128::from(yi),
+                |acc, (_, &(xj, _))| {
+                    assert!(xi != xj, "two samples share x = {}", xi);
+                    acc * Rational128::new(x - xj, xi - xj)
+                },
+            )
+        })
+        .fold(Rational128::zero(), |acc, term| acc + term)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extrapolate_next() {
+        assert_eq!(extrapolate_next(&[0, 3, 6, 9, 12, 15]), 18);
+        assert_eq!(extrapolate_next(&[1, 3, 6, 10, 15, 21]), 28);
+        assert_eq!(extrapolate_next(&[10i64, 13, 16, 21, 30, 45]), 68);
+        assert_eq!(extrapolate_next::<i32>(&[]), 0);
+    }
+
+    #[test]
+    fn test_extrapolate_prev() {
+        assert_eq!(extrapolate_prev(&[0, 3, 6, 9, 12, 15]), -3);
+        assert_eq!(extrapolate_prev(&[1, 3, 6, 10, 15, 21]), 0);
+        assert_eq!(extrapolate_prev(&[10i64, 13, 16, 21, 30, 45]), 5);
+    }
+
+    #[test]
+    fn test_polynomial_degree() {
+        assert_eq!(polynomial_degree(&[5, 5, 5]), Some(0));
+        assert_eq!(polynomial_degree(&[0, 3, 6, 9]), Some(1));
+        assert_eq!(polynomial_degree(&[1, 3, 6, 10, 15]), Some(2));
+        // Not enough values to tell a quadratic from a cubic
+        assert_eq!(polynomial_degree(&[1, 3, 6]), None);
+        assert_eq!(polynomial_degree::<i64>(&[]), None);
+    }
+
+    #[test]
+    fn test_interpolate() {
+        // y = 2x^2 - 3x + 1
+        let samples = [(0, 1), (1, 0), (2, 3)];
+        assert_eq!(interpolate(&samples, 10), Rational128::from(171));
+        // Samples can start anywhere, as in the infinite garden
+        let samples = [(65, 3), (196, 7), (327, 2)];
+        let at = |x: i128| interpolate(&samples, x);
+        assert_eq!(at(65), Rational128::from(3));
+        assert_eq!(at(327), Rational128::from(2));
+        assert_eq!(interpolate(&[(0, 1), (2, 2)], 1), Rational128::new(3, 2));
+    }
+}